@@ -0,0 +1,53 @@
+#![allow(clippy::needless_return)]
+
+use futures::StreamExt;
+use grapheners::prelude::*;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main () {
+
+    let config = Arc::new(ClientConfig::builder()
+        .env()
+        .subscription_buffer(16)
+        .build()
+        .expect("Failed to load client config"));
+
+    let mut concrete_ws = FastWebsocketClient::with_config(String::from("ws://127.0.0.1:8090"), Arc::clone(&config));
+
+    let mut graphene_client = GrapheneClient::with_config(config);
+
+    let tx_cb_call = graphene_client.create_callback_channel();
+
+    concrete_ws.set_callback_channel(tx_cb_call);
+
+    let tx_session = graphene_client.create_session_channel();
+
+    concrete_ws.set_session_channel(tx_session);
+
+    graphene_client.set_ws_connection(WebSocket::new(concrete_ws));
+
+    graphene_client.connect().await.expect("Failed to establish websocket connection with server");
+
+    graphene_client.init().await.expect("Failed to Initialize Client...");
+
+    let updates = graphene_client.account_updates(vec![AccountId::new(7)]).await
+        .expect("Failed to subscribe to account");
+
+    let names = updates
+        .flat_map(|update| futures::stream::iter(update.updated))
+        .filter_map(|object| async move {
+            if let ChainObject::Account(account) = object {
+                return Some(account.name);
+            } else {
+                return None;
+            }
+        });
+
+    futures::pin_mut!(names);
+
+    while let Some(name) = names.next().await {
+        println!("Account updated: {}", name);
+    }
+
+}
@@ -0,0 +1,50 @@
+#![allow(clippy::needless_return)]
+
+use futures::StreamExt;
+use grapheners::prelude::*;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main () {
+
+    let config = Arc::new(ClientConfig::builder()
+        .env()
+        .build()
+        .expect("Failed to load client config"));
+
+    let mut concrete_ws = FastWebsocketClient::with_config(String::from("ws://127.0.0.1:8090"), Arc::clone(&config));
+
+    let mut graphene_client = GrapheneClient::with_config(config);
+
+    let tx_cb_call = graphene_client.create_callback_channel();
+
+    concrete_ws.set_callback_channel(tx_cb_call);
+
+    let tx_session = graphene_client.create_session_channel();
+
+    concrete_ws.set_session_channel(tx_session);
+
+    graphene_client.set_ws_connection(WebSocket::new(concrete_ws));
+
+    graphene_client.connect().await.expect("Failed to establish websocket connection with server");
+
+    graphene_client.init().await.expect("Failed to Initialize Client...");
+
+    let mut blocks = graphene_client.subscribe_to_blocks().await
+        .expect("Failed to subscribe to blocks");
+
+    while let Some(block) = blocks.next().await {
+        println!(
+            "Block #{} at {} by {} with {} transactions",
+            block.block_num(),
+            block.header.timestamp,
+            block.header.witness,
+            block.transactions.len()
+        );
+    }
+
+    if let Some(block_num) = blocks.failed_block() {
+        println!("Block stream ended, block {} could not be fetched", block_num);
+    }
+
+}
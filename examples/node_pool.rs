@@ -0,0 +1,61 @@
+#![allow(clippy::needless_return)]
+
+use grapheners::prelude::*;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+#[tokio::main]
+async fn main () {
+
+    let urls = vec![
+        String::from("ws://127.0.0.1:8090"),
+        String::from("ws://127.0.0.1:8091")
+    ];
+
+    let config = Arc::new(ClientConfig::builder()
+        .env()
+        .timeout(Timeout::PoolHealthCheck, 10000)
+        .build()
+        .expect("Failed to load client config"));
+
+    let mut pool = NodePoolClient::with_config(urls, Arc::clone(&config));
+
+    let mut graphene_client = GrapheneClient::with_config(config);
+
+    let tx_cb_call = graphene_client.create_callback_channel();
+
+    pool.set_callback_channel(tx_cb_call);
+
+    let tx_session = graphene_client.create_session_channel();
+
+    pool.set_session_channel(tx_session);
+
+    let (tx_state, mut rx_state) = tokio::sync::mpsc::channel::<ConnectionState>(100);
+
+    pool.set_connection_state_channel(tx_state);
+
+    tokio::task::spawn(async move {
+        while let Some(state) = rx_state.recv().await {
+            println!("Connection State: {:?}", state);
+        }
+    });
+
+    graphene_client.set_ws_connection(WebSocket::new(pool));
+
+    graphene_client.connect().await.expect("Failed to connect to any node of the pool");
+
+    graphene_client.init().await.expect("Failed to Initialize Client...");
+
+    println!("Chain ID: {:?}", graphene_client.get_chain_id());
+
+    let dynamic_properties: Result<Value, GrapheneError> = graphene_client.call(GrapheneApi::Database(0), "get_dynamic_global_properties", ()).await;
+
+    match dynamic_properties {
+        Ok(properties) => println!("Dynamic Global Properties: {}", properties),
+        Err(error) => println!("Error getting dynamic global properties: {}", error)
+    }
+
+    graphene_client.close().await.expect("Failed to close websocket connection");
+
+}
@@ -1,5 +1,5 @@
 use std::fmt;
-use crate::config::config::CONFIG;
+use crate::config::config::ClientConfig;
 
 #[derive(Debug, Copy, Clone)]
 pub enum GrapheneApi {
@@ -7,6 +7,7 @@ pub enum GrapheneApi {
     Network(u8),
     History(u8),
     Crypto(u8),
+    Asset(u8),
     Custom(u8)
 }
 
@@ -18,31 +19,47 @@ impl fmt::Display for GrapheneApi {
             GrapheneApi::Network(_) => write!(f,"GrapheneApi::Network"),
             GrapheneApi::History(_) => write!(f,"GrapheneApi::History"),
             GrapheneApi::Crypto(_) => write!(f,"GrapheneApi::Crypto"),
+            GrapheneApi::Asset(_) => write!(f,"GrapheneApi::Asset"),
             GrapheneApi::Custom(_) => write!(f,"GrapheneApi::Custom"),
         }
     }
 }
 
-impl From<GrapheneApi> for u8 {
-    fn from(api_id: GrapheneApi) -> u8 {
-        match api_id {
-            GrapheneApi::Database(id) => id as u8,
-            GrapheneApi::Network(id) => id as u8,
-            GrapheneApi::History(id) => id as u8,
-            GrapheneApi::Crypto(id) => id as u8,
-            GrapheneApi::Custom(id) => id as u8,
+impl GrapheneApi {
+    pub fn name(&self, config: &ClientConfig) -> String {
+        match self {
+            GrapheneApi::Database(_id) => String::from("database"),
+            GrapheneApi::Network(_id) => String::from("network_broadcast"),
+            GrapheneApi::History(_id) => String::from("history"),
+            GrapheneApi::Crypto(_id) => String::from("crypto"),
+            GrapheneApi::Asset(_id) => String::from("asset"),
+            GrapheneApi::Custom(id) => config.get_custom_api_name(*id),
         }
     }
 }
 
-impl From<GrapheneApi> for String {
-    fn from(api_id: GrapheneApi) -> String {
+impl GrapheneApi {
+    pub fn from_name(name: &str) -> Option<GrapheneApi> {
+        match name {
+            "database" => Some(GrapheneApi::Database(0)),
+            "network_broadcast" => Some(GrapheneApi::Network(0)),
+            "history" => Some(GrapheneApi::History(0)),
+            "crypto" => Some(GrapheneApi::Crypto(0)),
+            "asset" => Some(GrapheneApi::Asset(0)),
+            _ => None
+        }
+    }
+}
+
+impl From<GrapheneApi> for u8 {
+    fn from(api_id: GrapheneApi) -> u8 {
         match api_id {
-            GrapheneApi::Database(_id) => String::from("database"),
-            GrapheneApi::Network(_id) => String::from("network_broadcast"),
-            GrapheneApi::History(_id) => String::from("history"),
-            GrapheneApi::Crypto(_id) => String::from("crypto"),
-            GrapheneApi::Custom(id) => CONFIG.get_custom_api_name(id),
+            GrapheneApi::Database(id) => id,
+            GrapheneApi::Network(id) => id,
+            GrapheneApi::History(id) => id,
+            GrapheneApi::Crypto(id) => id,
+            GrapheneApi::Asset(id) => id,
+            GrapheneApi::Custom(id) => id,
         }
     }
 }
@@ -52,9 +69,16 @@ pub struct GrapheneApis {
     network: Option<GrapheneApi>,
     history: Option<GrapheneApi>,
     crypto: Option<GrapheneApi>,
+    asset: Option<GrapheneApi>,
     custom: Option<Vec<GrapheneApi>>
 }
 
+impl Default for GrapheneApis {
+    fn default() -> Self {
+        return GrapheneApis::new();
+    }
+}
+
 impl GrapheneApis {
     pub fn new() -> Self {
         GrapheneApis {
@@ -62,6 +86,7 @@ impl GrapheneApis {
             network: None,
             history: None,
             crypto: None,
+            asset: None,
             custom: None
         }
     }
@@ -82,6 +107,10 @@ impl GrapheneApis {
         self.crypto = Some(GrapheneApi::Crypto(id));
     }
 
+    pub fn set_asset_api(&mut self, id:u8) {
+        self.asset = Some(GrapheneApi::Asset(id));
+    }
+
     pub fn set_custom_api(&mut self, ids:Vec<u8>) {
         self.custom = Some(ids.iter().map(|id| GrapheneApi::Custom(*id)).collect::<Vec<GrapheneApi>>());
     }
@@ -118,6 +147,47 @@ impl GrapheneApis {
         }
     }
 
+    pub fn get_asset_api(&self) -> Option<u8> {
+        if let Some(id) = self.asset {
+            return Some(id.into());
+        } else {
+            return None;
+        }
+    }
+
+    pub fn lookup(&self, api: GrapheneApi) -> Option<u8> {
+        match api {
+            GrapheneApi::Database(_) => self.get_database_api(),
+            GrapheneApi::Network(_) => self.get_network_api(),
+            GrapheneApi::History(_) => self.get_history_api(),
+            GrapheneApi::Crypto(_) => self.get_crypto_api(),
+            GrapheneApi::Asset(_) => self.get_asset_api(),
+            GrapheneApi::Custom(_) => None
+        }
+    }
+
+    pub fn set_api(&mut self, api: GrapheneApi, id: u8) {
+        match api {
+            GrapheneApi::Database(_) => self.set_database_api(id),
+            GrapheneApi::Network(_) => self.set_network_api(id),
+            GrapheneApi::History(_) => self.set_history_api(id),
+            GrapheneApi::Crypto(_) => self.set_crypto_api(id),
+            GrapheneApi::Asset(_) => self.set_asset_api(id),
+            GrapheneApi::Custom(_) => {}
+        }
+    }
+
+    pub fn clear_api(&mut self, api: GrapheneApi) {
+        match api {
+            GrapheneApi::Database(_) => self.database = None,
+            GrapheneApi::Network(_) => self.network = None,
+            GrapheneApi::History(_) => self.history = None,
+            GrapheneApi::Crypto(_) => self.crypto = None,
+            GrapheneApi::Asset(_) => self.asset = None,
+            GrapheneApi::Custom(_) => {}
+        }
+    }
+
     pub fn get_custom_api(&self) -> Option<Vec<u8>> {
         if let Some(ids) = &self.custom {
             return Some(ids.iter().map(|id| u8::from(*id)).collect::<Vec<u8>>());
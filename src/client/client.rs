@@ -1,65 +1,140 @@
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
-use tokio::sync::mpsc::Sender;
+use tokio::sync::mpsc::{channel, Sender};
 
 use crate::commands::getters::getters::ChainGetter;
 use crate::commands::subscriptions::subscriptions::ChainSubscriptions;
-use crate::WebSocket;
+use crate::websocket::service::WebSocket;
 use crate::websocket::errors::WebSocketError;
-use crate::websocket::implementations::message_channel::{MessageChannel, CallbackMessageChannel, WebsocketCloseRequest};
-use crate::websocket::interface::SubscriptionCallback;
+use crate::websocket::implementations::message_channel::CallbackMessageChannel;
+use crate::websocket::interface::{SessionEvent, SubscriptionCallback};
 use crate::client::apis::{GrapheneApi, GrapheneApis};
+use crate::client::errors::GrapheneError;
+use crate::client::subscription::{SubscriptionHandle, SubscriptionKind, SubscriptionSlot, SubscriptionSlots, SubscriptionStream, stream_callback};
+use crate::commands::subscriptions::responses::{AccountUpdate, filter_account_notice, parse_pending_transaction};
+use crate::models::transaction::SignedTransaction;
+use crate::models::market::MarketUpdate;
+use crate::models::object_id::{AccountId, AssetId, ObjectId};
+use crate::models::block::{SignedBlock, block_num_from_id};
+use crate::commands::rpc;
 use crate::websocket::implementations::listeners::spawn_callbacks_table;
+use crate::config::config::ClientConfig;
+use crate::websocket::implementations::timeouts::Timeout;
 
-pub struct GrapheneClient<'a> {
-    ws_service: Option<Rc<RefCell<&'a mut WebSocket<'a>>>>,
-    pub chain_getter: ChainGetter<'a>,
-    chain_subscriptions: ChainSubscriptions<'a>,
-    callback_id_counter: u64,
-    callback_channel: Option<CallbackMessageChannel>,
-    chain_id: Option<String>,
-    apis: GrapheneApis
+const BLOCK_FETCH_ATTEMPTS: u32 = 5;
+
+#[derive(Clone)]
+pub struct GrapheneClient {
+    ws_service: Option<WebSocket>,
+    pub chain_getter: ChainGetter,
+    chain_subscriptions: ChainSubscriptions,
+    callback_id_counter: Arc<AtomicU64>,
+    subscriptions: Arc<Mutex<HashMap<u64, SubscriptionKind>>>,
+    slots: Arc<tokio::sync::Mutex<SubscriptionSlots>>,
+    callback_channel: Option<Arc<CallbackMessageChannel>>,
+    callback_sender: Option<Sender<(u64, Value)>>,
+    chain_id: Arc<RwLock<Option<String>>>,
+    apis: Arc<RwLock<GrapheneApis>>,
+    config: Arc<ClientConfig>,
+    callback_error_channel: Option<Sender<(u64, WebSocketError)>>
 }
 
-impl <'a>GrapheneClient<'a> {
+impl Default for GrapheneClient {
+    fn default() -> Self {
+        return GrapheneClient::new();
+    }
+}
+
+impl GrapheneClient {
     pub fn new() -> Self {
+        return GrapheneClient::with_config(Arc::new(ClientConfig::default()));
+    }
+
+    pub fn with_config(config: Arc<ClientConfig>) -> Self {
+        let apis = Arc::new(RwLock::new(GrapheneApis::new()));
+
+        let mut chain_getter = ChainGetter::new();
+        chain_getter.set_apis(Arc::clone(&apis));
+        chain_getter.set_config(Arc::clone(&config));
+
         Self {
             ws_service: None,
-            chain_getter: ChainGetter::new(),
+            chain_getter,
             chain_subscriptions: ChainSubscriptions::new(),
-            callback_id_counter: 0,
+            callback_id_counter: Arc::new(AtomicU64::new(0)),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            slots: Arc::new(tokio::sync::Mutex::new(SubscriptionSlots::default())),
             callback_channel: None,
-            chain_id: None,
-            apis: GrapheneApis::new()
+            callback_sender: None,
+            chain_id: Arc::new(RwLock::new(None)),
+            apis,
+            config,
+            callback_error_channel: None
         }
     }
 
-    pub fn set_ws_connection(&mut self, ws: &'a mut WebSocket<'a>) {
-        let websocket = Rc::new(RefCell::new(ws));
-        self.chain_getter.set_ws_connection(Rc::clone(&websocket));
-        self.chain_subscriptions.set_ws_connection(Rc::clone(&websocket));
-        self.ws_service = Some(websocket);
+    pub fn set_callback_error_channel(&mut self, channel: Sender<(u64, WebSocketError)>) {
+        self.callback_error_channel = Some(channel);
     }
 
-    pub async fn connect(&mut self) -> Result<(),WebSocketError> {
+    pub fn get_config(&self) -> &ClientConfig {
+        return &self.config;
+    }
+
+    pub fn set_ws_connection(&mut self, ws: WebSocket) {
+        self.chain_getter.set_ws_connection(ws.clone());
+        self.chain_subscriptions.set_ws_connection(ws.clone());
+        self.ws_service = Some(ws);
+    }
+
+    pub fn get_chain_id(&self) -> Option<String> {
+        return self.chain_id.read().unwrap().clone();
+    }
+
+    pub async fn connect(&self) -> Result<(),WebSocketError> {
         if let Some(ws) = &self.ws_service {
-            return ws.borrow_mut().connect().await;
+            return ws.connect().await;
         } else {
             return Err(WebSocketError::ConnectionError);
         }
     }
 
-    pub async fn init(&mut self) -> Result<(),WebSocketError> {
-        let _result = self.login(Some(String::from("init0")), Some(String::from("password"))).await?;
-        self.chain_id = Some(self.chain_getter.get_chain_id().await?);
-        self.apis.set_database_api(self.chain_getter.get_chain_api_id(GrapheneApi::Database(0)).await?);
-        self.apis.set_network_api(self.chain_getter.get_chain_api_id(GrapheneApi::Network(0)).await?);
-        self.apis.set_history_api(self.chain_getter.get_chain_api_id(GrapheneApi::History(0)).await?);
-        self.apis.set_crypto_api(self.chain_getter.get_chain_api_id(GrapheneApi::Crypto(0)).await?);
+    pub async fn init(&self) -> Result<(),GrapheneError> {
+        self.login(Some(String::from("init0")), Some(String::from("password"))).await?;
+
+        let chain_id = self.chain_getter.get_chain_id().await?;
+        *self.chain_id.write().unwrap() = Some(chain_id);
+
+        let database_api = self.chain_getter.get_chain_api_id(GrapheneApi::Database(0)).await?;
+        let network_api = self.chain_getter.get_chain_api_id(GrapheneApi::Network(0)).await?;
+        let history_api = self.chain_getter.get_chain_api_id(GrapheneApi::History(0)).await?;
+        let crypto_api = self.chain_getter.get_chain_api_id(GrapheneApi::Crypto(0)).await?;
+        let asset_api = self.chain_getter.get_chain_api_id(GrapheneApi::Asset(0)).await;
+
+        let mut apis = self.apis.write().unwrap();
+        apis.set_database_api(database_api);
+        apis.set_network_api(network_api);
+        apis.set_history_api(history_api);
+        apis.set_crypto_api(crypto_api);
+
+        if let Ok(asset_api) = asset_api {
+            apis.set_asset_api(asset_api);
+        } else {
+            println!("Asset API is not enabled on this node");
+        }
+
         return Ok(());
     }
 
+    pub async fn call<T: DeserializeOwned, P: Serialize>(&self, api: GrapheneApi, method: &str, params: P) -> Result<T, GrapheneError> {
+        return self.chain_getter.call_api(api, method, params).await;
+    }
+
     pub fn create_callback_channel(&mut self) -> Sender<(u64, Value)>
     {
 
@@ -73,14 +148,19 @@ impl <'a>GrapheneClient<'a> {
             rx_ws_close
         ) = callback_channel.create_channel();
 
-        self.callback_channel = Some(callback_channel);
+        self.callback_channel = Some(Arc::new(callback_channel));
+
+        self.callback_sender = Some(tx_cb_call.clone());
+
+        let error_channel = self.callback_error_channel.clone();
 
         tokio::task::spawn(async move {
             spawn_callbacks_table(
                 rx_cb_register,
                 rx_cb_unregister,
                 rx_cb_call,
-                rx_ws_close
+                rx_ws_close,
+                error_channel
             ).await;
         });
 
@@ -88,7 +168,42 @@ impl <'a>GrapheneClient<'a> {
 
     }
 
-    pub async fn login(&mut self, username: Option<String>, password: Option<String>) -> Result<(),WebSocketError> {
+    pub fn create_session_channel(&mut self) -> Sender<SessionEvent>
+    {
+
+        let (tx_session, mut rx_session) = channel::<SessionEvent>(100);
+
+        let apis = Arc::clone(&self.apis);
+
+        let error_channel = self.callback_error_channel.clone();
+
+        tokio::task::spawn(async move {
+            while let Some(event) = rx_session.recv().await {
+                match event {
+                    SessionEvent::Resolved(key, result) => {
+                        let api_id = result.as_u64().and_then(|id| u8::try_from(id).ok());
+                        if let (Some(api), Some(api_id)) = (api_from_session_key(&key), api_id) {
+                            println!("{} API id changed to {} after reconnect", api, api_id);
+                            apis.write().unwrap().set_api(api, api_id);
+                        }
+                    }
+                    SessionEvent::Failed(key, error) => {
+                        println!("Session request {} failed after reconnect: {}", key, error);
+                        if let Some(api) = api_from_session_key(&key) {
+                            apis.write().unwrap().clear_api(api);
+                        } else if let (Some(callback_id), Some(channel)) = (callback_from_session_key(&key), &error_channel) {
+                            let _ = channel.send((callback_id, WebSocketError::SubscribingError)).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        return tx_session;
+
+    }
+
+    pub async fn login(&self, username: Option<String>, password: Option<String>) -> Result<(),GrapheneError> {
 
         if let Some(ws) = &self.ws_service {
 
@@ -107,45 +222,638 @@ impl <'a>GrapheneClient<'a> {
                 password_json = json!("");
             }
     
-            let req = json!({
-                "method": "call",
-                "params": [1, "login", [username_json, password_json]],
-                "id": 1
-            });
-    
-            let _ = ws.borrow_mut().send(req).await?;
-
-            let result = ws.borrow_mut().receive().await?;
+            let result: Value = rpc::session_call(ws, String::from("login"), 1, "login", (username_json, password_json)).await?;
     
             println!("Login Response: {:?}", result);
     
             return Ok(());
 
         } else {
-            return Err(WebSocketError::ConnectionError);
+            return Err(WebSocketError::ConnectionError.into());
         }
 
     }
 
-    pub async fn subscribe_to_account(&mut self, cb: Box<SubscriptionCallback>, accounts: Vec<String>) -> Result<(), WebSocketError> {
-        if let Ok(_res) = self.chain_subscriptions.get_full_accounts(self.callback_id_counter, accounts).await {
-            if let Some(callback_channel) = &self.callback_channel {
-                let _ = callback_channel.register_callback(self.callback_id_counter, cb).await;
-                self.callback_id_counter += 1;
-                return Ok(()); 
-            } else {
-                return Err(WebSocketError::SubscribingError);
-            }   
+    fn fan_out_callback(&self, slot: SubscriptionSlot) -> Box<SubscriptionCallback> {
+        let subscriptions = Arc::clone(&self.subscriptions);
+        let callback_sender = self.callback_sender.clone();
+        let seen_objects: Arc<Mutex<HashMap<u64, HashSet<ObjectId>>>> = Arc::new(Mutex::new(HashMap::new()));
+
+        return Box::new(move |notice| {
+            let mut deliveries: Vec<(u64, Value)> = Vec::new();
+
+            if let Some(notice) = notice {
+                let subscribers: Vec<(u64, SubscriptionKind)> = subscriptions.lock().unwrap()
+                    .iter()
+                    .filter(|(_callback_id, kind)| kind.slot() == Some(slot))
+                    .map(|(callback_id, kind)| (*callback_id, kind.clone()))
+                    .collect();
+
+                let mut seen_objects = seen_objects.lock().unwrap();
+                seen_objects.retain(|callback_id, _seen| subscribers.iter().any(|(subscriber_id, _kind)| subscriber_id == callback_id));
+
+                for (callback_id, kind) in subscribers {
+                    if let SubscriptionKind::Accounts(accounts) = kind {
+                        let seen = seen_objects.entry(callback_id).or_default();
+                        if let Some(filtered) = filter_account_notice(&notice, &accounts, seen) {
+                            deliveries.push((callback_id, filtered));
+                        }
+                    } else {
+                        deliveries.push((callback_id, notice.clone()));
+                    }
+                }
+            }
+
+            let callback_sender = callback_sender.clone();
+
+            return Box::pin(async move {
+                if let Some(sender) = callback_sender {
+                    for (callback_id, notice) in deliveries {
+                        let _ = sender.send((callback_id, notice)).await;
+                    }
+                }
+                return Ok(());
+            });
+        });
+    }
+
+    async fn set_slot_callback(&self, slot: SubscriptionSlot, slot_id: u64) -> Result<(), GrapheneError> {
+        match slot {
+            SubscriptionSlot::Accounts => return self.chain_subscriptions.set_subscribe_callback(slot_id).await,
+            SubscriptionSlot::Blocks => return self.chain_subscriptions.set_block_applied_callback(slot_id).await,
+            SubscriptionSlot::PendingTransactions => return self.chain_subscriptions.set_pending_transaction_callback(slot_id).await
+        }
+    }
+
+    async fn open_slot(&self, callback_channel: &CallbackMessageChannel, slot: SubscriptionSlot) -> Result<u64, GrapheneError> {
+        let slot_id = self.callback_id_counter.fetch_add(1, Ordering::SeqCst);
+
+        callback_channel.register_callback(slot_id, self.fan_out_callback(slot)).await?;
+
+        if let Err(error) = self.set_slot_callback(slot, slot_id).await {
+            let _ = callback_channel.unregister_callback(slot_id).await;
+            return Err(error);
+        }
+
+        return Ok(slot_id);
+    }
+
+    async fn release_slot(&self, callback_channel: &CallbackMessageChannel, slots: &mut SubscriptionSlots, slot: SubscriptionSlot) -> Result<(), GrapheneError> {
+        let in_use = self.subscriptions.lock().unwrap().values().any(|kind| kind.slot() == Some(slot));
+
+        if in_use {
+            return Ok(());
+        }
+
+        if let Some(slot_id) = slots.get(slot) {
+            self.chain_subscriptions.cancel_all_subscriptions(vec![slot_id]).await?;
+            slots.set(slot, None);
+            callback_channel.unregister_callback(slot_id).await?;
+            return self.resubscribe(slots).await;
+        }
+
+        return Ok(());
+    }
+
+    async fn resubscribe(&self, slots: &SubscriptionSlots) -> Result<(), GrapheneError> {
+        for slot in [SubscriptionSlot::Accounts, SubscriptionSlot::Blocks, SubscriptionSlot::PendingTransactions] {
+            if let Some(slot_id) = slots.get(slot) {
+                self.set_slot_callback(slot, slot_id).await?;
+            }
+        }
+
+        let subscriptions: Vec<(u64, SubscriptionKind)> = self.subscriptions.lock().unwrap()
+            .iter()
+            .map(|(callback_id, kind)| (*callback_id, kind.clone()))
+            .collect();
+
+        for (callback_id, kind) in subscriptions {
+            match kind {
+                SubscriptionKind::Accounts(accounts) => self.chain_subscriptions.subscribe_to_accounts(callback_id, accounts).await?,
+                SubscriptionKind::Market(base, quote) => self.chain_subscriptions.subscribe_to_market(callback_id, base, quote).await?,
+                SubscriptionKind::Blocks | SubscriptionKind::PendingTransactions(_) => {}
+            }
+        }
+
+        return Ok(());
+    }
+
+    async fn subscribe(&self, cb: Box<SubscriptionCallback>, kind: SubscriptionKind) -> Result<SubscriptionHandle, GrapheneError> {
+        let callback_channel = if let Some(callback_channel) = &self.callback_channel {
+            callback_channel
         } else {
-            return Err(WebSocketError::SubscribingError);
+            return Err(WebSocketError::SubscribingError.into());
+        };
+
+        let mut slots = self.slots.lock().await;
+
+        if let Some(slot) = kind.slot() {
+            if slots.get(slot).is_none() {
+                let slot_id = self.open_slot(callback_channel, slot).await?;
+                slots.set(slot, Some(slot_id));
+            }
         }
+
+        let callback_id = self.callback_id_counter.fetch_add(1, Ordering::SeqCst);
+
+        callback_channel.register_callback(callback_id, cb).await?;
+
+        let subscribed = match &kind {
+            SubscriptionKind::Accounts(accounts) => self.chain_subscriptions.subscribe_to_accounts(callback_id, accounts.clone()).await,
+            SubscriptionKind::Market(base, quote) => self.chain_subscriptions.subscribe_to_market(callback_id, *base, *quote).await,
+            SubscriptionKind::Blocks | SubscriptionKind::PendingTransactions(_) => Ok(())
+        };
+
+        if let Err(error) = subscribed {
+            let _ = callback_channel.unregister_callback(callback_id).await;
+            if let Some(slot) = kind.slot() {
+                let _ = self.release_slot(callback_channel, &mut slots, slot).await;
+            }
+            return Err(error);
+        }
+
+        self.subscriptions.lock().unwrap().insert(callback_id, kind.clone());
+        return Ok(SubscriptionHandle::new(callback_id, kind, self.clone()));
+    }
+
+    pub async fn subscribe_to_account(&self, cb: Box<SubscriptionCallback>, accounts: Vec<AccountId>) -> Result<SubscriptionHandle, GrapheneError> {
+        return self.subscribe(cb, SubscriptionKind::Accounts(accounts)).await;
     }
 
-    pub async fn close(&mut self) -> Result<(),WebSocketError> {
+    pub async fn account_updates(&self, accounts: Vec<AccountId>) -> Result<SubscriptionStream<AccountUpdate>, GrapheneError> {
+        let filter = accounts.clone();
+        let seen_objects: Mutex<HashSet<ObjectId>> = Mutex::new(HashSet::new());
+
+        let (callback, receiver, lagged) = stream_callback(self.config.get_subscription_buffer(), move |notice| {
+            return filter_account_notice(&notice, &filter, &mut seen_objects.lock().unwrap())
+                .and_then(AccountUpdate::from_notice);
+        });
+
+        let handle = self.subscribe_to_account(callback, accounts).await?;
+        return Ok(SubscriptionStream::new(receiver, lagged, handle));
+    }
+
+    pub async fn subscribe_to_blocks(&self) -> Result<SubscriptionStream<SignedBlock>, GrapheneError> {
+        let capacity = self.config.get_subscription_buffer();
+
+        let (callback, mut block_ids, lagged) = stream_callback(capacity, |notice| {
+            return notice.as_array()
+                .and_then(|params| params.first())
+                .unwrap_or(&notice)
+                .as_str()
+                .map(String::from);
+        });
+
+        let handle = self.subscribe(callback, SubscriptionKind::Blocks).await?;
+
+        let (sender, receiver) = channel::<SignedBlock>(capacity);
+
+        let stream = SubscriptionStream::new(receiver, lagged, handle);
+
+        let failed_block = stream.failed_block_flag();
+
+        let chain_getter = self.chain_getter.clone();
+
+        let config = Arc::clone(&self.config);
+
+        tokio::task::spawn(async move {
+            let mut last_block_num: Option<u32> = None;
+
+            while let Some(block_id) = block_ids.recv().await {
+                let block_num = if let Some(block_num) = block_num_from_id(&block_id) {
+                    block_num
+                } else {
+                    println!("Error parsing block number from block id: {}", block_id);
+                    continue;
+                };
+
+                let first_block_num = match last_block_num {
+                    Some(last) if last < block_num => last + 1,
+                    _ => block_num
+                };
+
+                for num in first_block_num..=block_num {
+                    let block = if let Some(block) = fetch_block(&chain_getter, num, &config).await {
+                        block
+                    } else {
+                        println!("Ending block stream, block {} could not be fetched", num);
+                        *failed_block.lock().unwrap() = Some(num);
+                        return;
+                    };
+
+                    if sender.send(block).await.is_err() {
+                        return;
+                    }
+
+                    last_block_num = Some(num);
+                }
+            }
+        });
+
+        return Ok(stream);
+    }
+
+    pub async fn subscribe_to_pending_transactions(&self, accounts: Vec<AccountId>) -> Result<SubscriptionStream<SignedTransaction>, GrapheneError> {
+        let filter = accounts.clone();
+
+        let (callback, receiver, lagged) = stream_callback(self.config.get_subscription_buffer(), move |notice| {
+            return parse_pending_transaction(notice)
+                .filter(|transaction| filter.is_empty() || transaction.involves_any(&filter));
+        });
+
+        let handle = self.subscribe(callback, SubscriptionKind::PendingTransactions(accounts)).await?;
+
+        return Ok(SubscriptionStream::new(receiver, lagged, handle));
+    }
+
+    pub async fn subscribe_to_market(&self, cb: Box<SubscriptionCallback>, base: AssetId, quote: AssetId) -> Result<SubscriptionHandle, GrapheneError> {
+        return self.subscribe(cb, SubscriptionKind::Market(base, quote)).await;
+    }
+
+    pub async fn market_updates(&self, base: AssetId, quote: AssetId) -> Result<SubscriptionStream<MarketUpdate>, GrapheneError> {
+        let (callback, receiver, lagged) = stream_callback(self.config.get_subscription_buffer(), MarketUpdate::from_notice);
+        let handle = self.subscribe_to_market(callback, base, quote).await?;
+        return Ok(SubscriptionStream::new(receiver, lagged, handle));
+    }
+
+    pub(crate) async fn unsubscribe(&self, callback_id: u64) -> Result<(), GrapheneError> {
+        let callback_channel = if let Some(callback_channel) = &self.callback_channel {
+            callback_channel
+        } else {
+            return Err(WebSocketError::SubscribingError.into());
+        };
+
+        let mut slots = self.slots.lock().await;
+
+        let kind = if let Some(kind) = self.subscriptions.lock().unwrap().get(&callback_id).cloned() {
+            kind
+        } else {
+            return Ok(());
+        };
+
+        match &kind {
+            SubscriptionKind::Market(base, quote) => self.chain_subscriptions.unsubscribe_from_market(callback_id, *base, *quote).await?,
+            SubscriptionKind::Accounts(accounts) => {
+                let watched: HashSet<AccountId> = self.subscriptions.lock().unwrap()
+                    .iter()
+                    .filter(|(other_id, _kind)| **other_id != callback_id)
+                    .filter_map(|(_other_id, kind)| if let SubscriptionKind::Accounts(accounts) = kind { Some(accounts.clone()) } else { None })
+                    .flatten()
+                    .collect();
+
+                let released: Vec<AccountId> = accounts.iter().filter(|account| !watched.contains(account)).copied().collect();
+
+                self.chain_subscriptions.unsubscribe_from_accounts(callback_id, released).await?;
+            }
+            SubscriptionKind::Blocks | SubscriptionKind::PendingTransactions(_) => {}
+        }
+
+        self.subscriptions.lock().unwrap().remove(&callback_id);
+
+        callback_channel.unregister_callback(callback_id).await?;
+
+        if let Some(slot) = kind.slot() {
+            self.release_slot(callback_channel, &mut slots, slot).await?;
+        }
+
+        return Ok(());
+    }
+
+    pub async fn cancel_all_subscriptions(&self) -> Result<(), GrapheneError> {
+        let mut slots = self.slots.lock().await;
+
+        let mut callback_ids: Vec<u64> = self.subscriptions.lock().unwrap().keys().copied().collect();
+
+        callback_ids.extend(slots.callback_ids());
+
+        self.chain_subscriptions.cancel_all_subscriptions(callback_ids.clone()).await?;
+
+        self.subscriptions.lock().unwrap().clear();
+
+        *slots = SubscriptionSlots::default();
+
+        if let Some(callback_channel) = &self.callback_channel {
+            for callback_id in callback_ids.iter() {
+                callback_channel.unregister_callback(*callback_id).await?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub async fn close(&self) -> Result<(),WebSocketError> {
         if let Some(ws) = &self.ws_service {
-            return ws.borrow_mut().close().await;
+            let result = ws.close().await;
+
+            self.subscriptions.lock().unwrap().clear();
+
+            *self.slots.lock().await = SubscriptionSlots::default();
+
+            if let Some(callback_channel) = &self.callback_channel {
+                callback_channel.drop_callback_list().await?;
+            }
+
+            return result;
         } else {
             return Err(WebSocketError::ConnectionError);
         }
     }
+}
+
+fn api_from_session_key(key: &str) -> Option<GrapheneApi> {
+    return key.strip_prefix("api:").and_then(GrapheneApi::from_name);
+}
+
+async fn fetch_block(chain_getter: &ChainGetter, block_num: u32, config: &ClientConfig) -> Option<SignedBlock> {
+    let max_backoff = config.get_timeout(Timeout::BlockFetchMax);
+
+    let mut backoff = config.get_timeout(Timeout::BlockFetchMin);
+
+    for attempt in 1..=BLOCK_FETCH_ATTEMPTS {
+        match chain_getter.get_block(block_num).await {
+            Ok(Some(block)) => return Some(block),
+            Ok(None) => println!("Block {} not found (attempt {})", block_num, attempt),
+            Err(error) => println!("Error fetching block {} (attempt {}): {}", block_num, attempt, error)
+        }
+
+        if attempt < BLOCK_FETCH_ATTEMPTS {
+            tokio::time::sleep(Duration::from_millis(backoff)).await;
+            backoff = std::cmp::min(backoff.saturating_mul(2), max_backoff);
+        }
+    }
+
+    return None;
+}
+
+fn callback_from_session_key(key: &str) -> Option<u64> {
+    return key.strip_prefix("subscription:")
+        .and_then(|rest| rest.split(':').next())
+        .and_then(|callback_id| callback_id.parse::<u64>().ok());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+    use futures::StreamExt;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+    use crate::websocket::interface::IWebSocket;
+
+    #[derive(Default)]
+    struct MockState {
+        calls: Mutex<Vec<(String, Value)>>,
+        targets: Mutex<Vec<(u64, String)>>,
+        results: Mutex<HashMap<String, Value>>,
+        session: Mutex<Vec<String>>,
+        failing: Mutex<HashSet<String>>
+    }
+
+    impl MockState {
+        fn count(&self, method: &str) -> usize {
+            return self.calls.lock().unwrap().iter().filter(|(called, _params)| called == method).count();
+        }
+
+        fn methods(&self) -> Vec<String> {
+            return self.calls.lock().unwrap().iter().map(|(method, _params)| method.clone()).collect();
+        }
+
+        fn params(&self, method: &str) -> Option<Value> {
+            return self.calls.lock().unwrap().iter().rev().find(|(called, _params)| called == method).map(|(_method, params)| params.clone());
+        }
+
+        fn has_session(&self, key: &str) -> bool {
+            return self.session.lock().unwrap().iter().any(|existing| existing == key);
+        }
+    }
+
+    struct MockSocket {
+        state: Arc<MockState>
+    }
+
+    impl IWebSocket for MockSocket {
+        fn connect(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Ok(()) });
+        }
+
+        fn send(&self, _msg: Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Ok(()) });
+        }
+
+        fn receive(&self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Err(WebSocketError::MessageReceiveError) });
+        }
+
+        fn call(&self, msg: Value) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+            let method = msg.pointer("/params/1").and_then(|method| method.as_str()).unwrap_or("").to_string();
+            let params = msg.pointer("/params/2").cloned().unwrap_or(Value::Null);
+            let api_id = msg.pointer("/params/0").and_then(|api_id| api_id.as_u64()).unwrap_or(0);
+            let failing = self.state.failing.lock().unwrap().contains(&method);
+            let result = self.state.results.lock().unwrap().get(&method).cloned().unwrap_or(Value::Null);
+
+            self.state.targets.lock().unwrap().push((api_id, method.clone()));
+            self.state.calls.lock().unwrap().push((method, params));
+
+            return Box::pin(async move {
+                if failing {
+                    return Ok(json!({"id": 1, "error": {"code": 1, "message": "rejected"}}));
+                }
+                return Ok(json!({"id": 1, "result": result}));
+            });
+        }
+
+        fn add_session_request(&self, key: String, _request: Value, _result: Option<Value>) {
+            let mut session = self.state.session.lock().unwrap();
+            if !session.contains(&key) {
+                session.push(key);
+            }
+        }
+
+        fn remove_session_request(&self, key: &str) {
+            self.state.session.lock().unwrap().retain(|existing| existing != key);
+        }
+
+        fn close(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Ok(()) });
+        }
+    }
+
+    fn mock_client() -> (GrapheneClient, Arc<MockState>, Sender<(u64, Value)>) {
+        let state = Arc::new(MockState::default());
+        let mut client = GrapheneClient::new();
+        let tx_cb_call = client.create_callback_channel();
+        client.set_ws_connection(WebSocket::new(MockSocket { state: Arc::clone(&state) }));
+        return (client, state, tx_cb_call);
+    }
+
+    fn recording_callback() -> (Box<SubscriptionCallback>, UnboundedReceiver<Value>) {
+        let (tx_notice, rx_notice) = unbounded_channel::<Value>();
+        let callback: Box<SubscriptionCallback> = Box::new(move |notice| {
+            if let Some(notice) = notice {
+                let _ = tx_notice.send(notice);
+            }
+            return Box::pin(async { Ok(()) });
+        });
+        return (callback, rx_notice);
+    }
+
+    #[tokio::test]
+    async fn unresolved_api_is_resolved_before_calling() {
+        let (client, state, _tx_cb_call) = mock_client();
+
+        state.results.lock().unwrap().insert(String::from("history"), json!(3));
+
+        let _history: Value = client.call(GrapheneApi::History(0), "get_account_history", ("1.2.7", "1.11.0", 10, "1.11.0")).await.unwrap();
+        let _again: Value = client.call(GrapheneApi::History(0), "get_account_history", ("1.2.7", "1.11.0", 10, "1.11.0")).await.unwrap();
+
+        assert_eq!(*state.targets.lock().unwrap(), vec![
+            (1, String::from("history")),
+            (3, String::from("get_account_history")),
+            (3, String::from("get_account_history"))
+        ]);
+    }
+
+    #[tokio::test]
+    async fn failed_api_resolution_is_an_error() {
+        let (client, state, _tx_cb_call) = mock_client();
+
+        state.failing.lock().unwrap().insert(String::from("history"));
+
+        let history: Result<Value, GrapheneError> = client.call(GrapheneApi::History(0), "get_account_history", ("1.2.7", "1.11.0", 10, "1.11.0")).await;
+
+        assert!(history.is_err());
+        assert_eq!(state.count("get_account_history"), 0);
+    }
+
+    #[tokio::test]
+    async fn block_subscriptions_share_one_node_callback() {
+        let (client, state, tx_cb_call) = mock_client();
+        let (first_cb, mut first_rx) = recording_callback();
+        let (second_cb, mut second_rx) = recording_callback();
+
+        let _first = client.subscribe(first_cb, SubscriptionKind::Blocks).await.unwrap();
+        let _second = client.subscribe(second_cb, SubscriptionKind::Blocks).await.unwrap();
+
+        assert_eq!(state.count("set_block_applied_callback"), 1);
+
+        let slot_id = state.params("set_block_applied_callback").unwrap()[0].as_u64().unwrap();
+
+        tx_cb_call.send((slot_id, json!(["00000010"]))).await.unwrap();
+
+        assert_eq!(first_rx.recv().await, Some(json!(["00000010"])));
+        assert_eq!(second_rx.recv().await, Some(json!(["00000010"])));
+    }
+
+    #[tokio::test]
+    async fn account_notices_only_reach_subscribers_watching_them() {
+        let (client, state, tx_cb_call) = mock_client();
+        let (watch_seven_cb, mut watch_seven_rx) = recording_callback();
+        let (watch_six_cb, mut watch_six_rx) = recording_callback();
+
+        let _seven = client.subscribe(watch_seven_cb, SubscriptionKind::Accounts(vec![AccountId::new(7)])).await.unwrap();
+        let _six = client.subscribe(watch_six_cb, SubscriptionKind::Accounts(vec![AccountId::new(6)])).await.unwrap();
+
+        let slot_id = state.params("set_subscribe_callback").unwrap()[0].as_u64().unwrap();
+
+        let six_notice = json!([[
+            {"id": "1.2.6", "name": "six"},
+            {"id": "2.5.1", "owner": "1.2.6", "asset_type": "1.3.0", "balance": 10}
+        ]]);
+        let seven_notice = json!([[
+            {"id": "2.5.2", "owner": "1.2.7", "asset_type": "1.3.0", "balance": 20},
+            {"id": "1.7.3", "seller": "1.2.9"}
+        ]]);
+
+        tx_cb_call.send((slot_id, six_notice.clone())).await.unwrap();
+        tx_cb_call.send((slot_id, json!([["2.5.1", "2.5.2"]]))).await.unwrap();
+        tx_cb_call.send((slot_id, seven_notice)).await.unwrap();
+
+        assert_eq!(watch_six_rx.recv().await, Some(six_notice));
+        assert_eq!(watch_six_rx.recv().await, Some(json!([["2.5.1"]])));
+        assert_eq!(watch_seven_rx.recv().await, Some(json!([[{"id": "2.5.2", "owner": "1.2.7", "asset_type": "1.3.0", "balance": 20}]])));
+    }
+
+    #[tokio::test]
+    async fn account_updates_stream_is_scoped_to_requested_accounts() {
+        let (client, state, tx_cb_call) = mock_client();
+
+        let mut updates = client.account_updates(vec![AccountId::new(7)]).await.unwrap();
+
+        let slot_id = state.params("set_subscribe_callback").unwrap()[0].as_u64().unwrap();
+
+        tx_cb_call.send((slot_id, json!([[
+            {"id": "2.5.1", "owner": "1.2.6", "asset_type": "1.3.0", "balance": 10},
+            {"id": "2.5.2", "owner": "1.2.7", "asset_type": "1.3.0", "balance": 20},
+            "1.7.3"
+        ]]))).await.unwrap();
+        tx_cb_call.send((slot_id, json!([[{"id": "1.2.6", "name": "six"}]]))).await.unwrap();
+        tx_cb_call.send((slot_id, json!([["2.5.2"]]))).await.unwrap();
+
+        let first = updates.next().await.unwrap();
+        assert_eq!(first.updated.len(), 1);
+        assert_eq!(first.updated[0].get_id(), Some(ObjectId::new(2, 5, 2)));
+        assert!(first.removed.is_empty());
+
+        let second = updates.next().await.unwrap();
+        assert!(second.updated.is_empty());
+        assert_eq!(second.removed, vec![ObjectId::new(2, 5, 2)]);
+    }
+
+    #[tokio::test]
+    async fn releasing_a_slot_reissues_remaining_subscriptions() {
+        let (client, state, _tx_cb_call) = mock_client();
+        let (account_cb, _account_rx) = recording_callback();
+        let (first_cb, _first_rx) = recording_callback();
+        let (second_cb, _second_rx) = recording_callback();
+
+        let account = client.subscribe(account_cb, SubscriptionKind::Accounts(vec![AccountId::new(7)])).await.unwrap();
+        let first = client.subscribe(first_cb, SubscriptionKind::Blocks).await.unwrap();
+        let second = client.subscribe(second_cb, SubscriptionKind::Blocks).await.unwrap();
+
+        let slot_id = state.params("set_block_applied_callback").unwrap()[0].as_u64().unwrap();
+
+        first.unsubscribe().await.unwrap();
+
+        assert_eq!(state.count("cancel_all_subscriptions"), 0);
+
+        state.calls.lock().unwrap().clear();
+
+        second.unsubscribe().await.unwrap();
+
+        assert_eq!(state.methods(), vec!["cancel_all_subscriptions", "set_subscribe_callback", "get_objects"]);
+        assert!(!state.has_session(&format!("subscription:{}:blocks", slot_id)));
+        assert!(state.has_session(&format!("subscription:{}:objects", account.get_callback_id())));
+    }
+
+    #[tokio::test]
+    async fn account_unsubscribe_keeps_accounts_watched_elsewhere() {
+        let (client, state, _tx_cb_call) = mock_client();
+        let (first_cb, _first_rx) = recording_callback();
+        let (second_cb, _second_rx) = recording_callback();
+
+        let first = client.subscribe(first_cb, SubscriptionKind::Accounts(vec![AccountId::new(7), AccountId::new(6)])).await.unwrap();
+        let _second = client.subscribe(second_cb, SubscriptionKind::Accounts(vec![AccountId::new(7)])).await.unwrap();
+
+        assert_eq!(state.count("set_subscribe_callback"), 1);
+
+        first.unsubscribe().await.unwrap();
+
+        assert_eq!(state.params("unsubscribe_from_accounts"), Some(json!([["1.2.6"]])));
+        assert_eq!(state.count("cancel_all_subscriptions"), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_unsubscribe_keeps_session_requests() {
+        let (client, state, _tx_cb_call) = mock_client();
+        let (market_cb, _market_rx) = recording_callback();
+
+        let market = client.subscribe(market_cb, SubscriptionKind::Market(AssetId::new(0), AssetId::new(1))).await.unwrap();
+        let callback_id = market.get_callback_id();
+
+        state.failing.lock().unwrap().insert(String::from("unsubscribe_from_market"));
+
+        assert!(market.unsubscribe().await.is_err());
+        assert!(state.has_session(&format!("subscription:{}:market", callback_id)));
+        assert!(client.subscriptions.lock().unwrap().contains_key(&callback_id));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,217 @@
+use std::error::Error;
+use std::fmt;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::websocket::errors::WebSocketError;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FcLogContext {
+    #[serde(default)]
+    pub level: String,
+    #[serde(default)]
+    pub file: String,
+    #[serde(default)]
+    pub line: u64,
+    #[serde(default)]
+    pub method: String,
+    #[serde(default)]
+    pub hostname: String,
+    #[serde(default)]
+    pub thread_name: String,
+    #[serde(default)]
+    pub timestamp: String
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FcLogMessage {
+    #[serde(default)]
+    pub context: FcLogContext,
+    #[serde(default)]
+    pub format: String,
+    #[serde(default)]
+    pub data: Value
+}
+
+impl FcLogMessage {
+    pub fn formatted(&self) -> String {
+        let mut message = self.format.clone();
+
+        if let Some(data) = self.data.as_object() {
+            for (key, value) in data {
+                let rendered = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string()
+                };
+                message = message.replace(&format!("${{{}}}", key), &rendered);
+            }
+        }
+
+        return message;
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FcException {
+    #[serde(default)]
+    pub code: i64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub stack: Vec<FcLogMessage>
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RpcError {
+    #[serde(default)]
+    pub code: i64,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<FcException>
+}
+
+impl RpcError {
+    pub fn from_value(error: Value) -> Self {
+        if let Ok(rpc_error) = serde_json::from_value::<RpcError>(error.clone()) {
+            return rpc_error;
+        } else {
+            return RpcError {
+                code: 0,
+                message: error.to_string(),
+                data: None
+            };
+        }
+    }
+
+    pub fn exception_name(&self) -> Option<&str> {
+        return self.data.as_ref().map(|exception| exception.name.as_str());
+    }
+
+    pub fn exception_code(&self) -> Option<i64> {
+        return self.data.as_ref().map(|exception| exception.code);
+    }
+
+    pub fn stack(&self) -> &[FcLogMessage] {
+        if let Some(exception) = &self.data {
+            return exception.stack.as_slice();
+        } else {
+            return &[];
+        }
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RPC error {}: {}", self.code, self.message)?;
+
+        if let Some(name) = self.exception_name() {
+            write!(f, " ({})", name)?;
+        }
+
+        for entry in self.stack() {
+            write!(f, "\n    {}:{} {}: {}", entry.context.file, entry.context.line, entry.context.method, entry.formatted())?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl Error for RpcError {}
+
+#[derive(Debug, Clone)]
+pub enum GrapheneError {
+    WebSocket(WebSocketError),
+    Rpc(RpcError),
+    Serialization(String)
+}
+
+impl fmt::Display for GrapheneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrapheneError::WebSocket(error) => write!(f,"{}", error),
+            GrapheneError::Rpc(error) => write!(f,"{}", error),
+            GrapheneError::Serialization(error) => write!(f,"Error on serializing or parsing node message: {}", error),
+        }
+    }
+}
+
+impl Error for GrapheneError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GrapheneError::WebSocket(error) => Some(error),
+            GrapheneError::Rpc(error) => Some(error),
+            GrapheneError::Serialization(_) => None,
+        }
+    }
+}
+
+impl From<WebSocketError> for GrapheneError {
+    fn from(error: WebSocketError) -> GrapheneError {
+        GrapheneError::WebSocket(error)
+    }
+}
+
+impl From<RpcError> for GrapheneError {
+    fn from(error: RpcError) -> GrapheneError {
+        GrapheneError::Rpc(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn formatted_substitutes_data_fields() {
+        let message: FcLogMessage = serde_json::from_value(json!({
+            "format": "Account ${name} has ${amount} of ${asset}",
+            "data": {"name": "init0", "amount": 100, "asset": "1.3.0"}
+        })).unwrap();
+
+        assert_eq!(message.formatted(), "Account init0 has 100 of 1.3.0");
+    }
+
+    #[test]
+    fn formatted_leaves_unknown_placeholders() {
+        let message: FcLogMessage = serde_json::from_value(json!({
+            "format": "missing ${what}",
+            "data": {}
+        })).unwrap();
+
+        assert_eq!(message.formatted(), "missing ${what}");
+    }
+
+    #[test]
+    fn rpc_error_display_includes_exception_and_stack() {
+        let error = RpcError::from_value(json!({
+            "code": 1,
+            "message": "Assert Exception",
+            "data": {
+                "code": 10,
+                "name": "assert_exception",
+                "message": "Assert Exception",
+                "stack": [{
+                    "context": {"file": "db_block.cpp", "line": 42, "method": "push_block"},
+                    "format": "block ${num} rejected",
+                    "data": {"num": 7}
+                }]
+            }
+        }));
+
+        assert_eq!(error.exception_name(), Some("assert_exception"));
+        assert_eq!(error.exception_code(), Some(10));
+        assert_eq!(error.to_string(), "RPC error 1: Assert Exception (assert_exception)\n    db_block.cpp:42 push_block: block 7 rejected");
+    }
+
+    #[test]
+    fn rpc_error_display_falls_back_to_raw_value() {
+        let error = RpcError::from_value(json!("plain failure"));
+
+        assert_eq!(error.stack().len(), 0);
+        assert_eq!(error.to_string(), "RPC error 0: \"plain failure\"");
+    }
+}
@@ -1,2 +1,4 @@
 pub mod client;
-pub mod apis;
\ No newline at end of file
+pub mod apis;
+pub mod errors;
+pub mod subscription;
\ No newline at end of file
@@ -0,0 +1,196 @@
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use futures::Stream;
+use serde_json::Value;
+use tokio::sync::mpsc::{channel, Receiver};
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::client::client::GrapheneClient;
+use crate::client::errors::GrapheneError;
+use crate::websocket::errors::WebSocketError;
+use crate::websocket::interface::SubscriptionCallback;
+use crate::models::object_id::{AccountId, AssetId};
+
+#[derive(Debug, Clone)]
+pub enum SubscriptionKind {
+    Accounts(Vec<AccountId>),
+    Blocks,
+    PendingTransactions(Vec<AccountId>),
+    Market(AssetId, AssetId)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum SubscriptionSlot {
+    Accounts,
+    Blocks,
+    PendingTransactions
+}
+
+impl SubscriptionKind {
+    pub(crate) fn slot(&self) -> Option<SubscriptionSlot> {
+        match self {
+            SubscriptionKind::Accounts(_accounts) => Some(SubscriptionSlot::Accounts),
+            SubscriptionKind::Blocks => Some(SubscriptionSlot::Blocks),
+            SubscriptionKind::PendingTransactions(_accounts) => Some(SubscriptionSlot::PendingTransactions),
+            SubscriptionKind::Market(_base, _quote) => None
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SubscriptionSlots {
+    accounts: Option<u64>,
+    blocks: Option<u64>,
+    pending_transactions: Option<u64>
+}
+
+impl SubscriptionSlots {
+    pub(crate) fn get(&self, slot: SubscriptionSlot) -> Option<u64> {
+        match slot {
+            SubscriptionSlot::Accounts => self.accounts,
+            SubscriptionSlot::Blocks => self.blocks,
+            SubscriptionSlot::PendingTransactions => self.pending_transactions
+        }
+    }
+
+    pub(crate) fn set(&mut self, slot: SubscriptionSlot, callback_id: Option<u64>) {
+        match slot {
+            SubscriptionSlot::Accounts => self.accounts = callback_id,
+            SubscriptionSlot::Blocks => self.blocks = callback_id,
+            SubscriptionSlot::PendingTransactions => self.pending_transactions = callback_id
+        }
+    }
+
+    pub(crate) fn callback_ids(&self) -> Vec<u64> {
+        return [self.accounts, self.blocks, self.pending_transactions].into_iter().flatten().collect();
+    }
+}
+
+pub struct SubscriptionHandle {
+    callback_id: u64,
+    kind: SubscriptionKind,
+    client: GrapheneClient,
+    active: bool
+}
+
+impl SubscriptionHandle {
+    pub(crate) fn new(callback_id: u64, kind: SubscriptionKind, client: GrapheneClient) -> Self {
+        Self {
+            callback_id,
+            kind,
+            client,
+            active: true
+        }
+    }
+
+    pub fn get_callback_id(&self) -> u64 {
+        return self.callback_id;
+    }
+
+    pub fn get_kind(&self) -> &SubscriptionKind {
+        return &self.kind;
+    }
+
+    pub async fn unsubscribe(mut self) -> Result<(), GrapheneError> {
+        self.active = false;
+        return self.client.unsubscribe(self.callback_id).await;
+    }
+}
+
+impl Drop for SubscriptionHandle {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let client = self.client.clone();
+            let callback_id = self.callback_id;
+            runtime.spawn(async move {
+                if let Err(error) = client.unsubscribe(callback_id).await {
+                    println!("Error unsubscribing callback {}: {}", callback_id, error);
+                }
+            });
+        } else {
+            println!("No runtime available to unsubscribe callback {}", self.callback_id);
+        }
+    }
+}
+
+pub(crate) fn stream_callback<T, F>(capacity: usize, parse: F) -> (Box<SubscriptionCallback>, Receiver<T>, Arc<AtomicU64>)
+where
+    T: Send + 'static,
+    F: Fn(Value) -> Option<T> + Send + Sync + 'static
+{
+    let (sender, receiver) = channel::<T>(capacity);
+
+    let lagged = Arc::new(AtomicU64::new(0));
+
+    let callback_lagged = Arc::clone(&lagged);
+
+    let callback: Box<SubscriptionCallback> = Box::new(move |notice| {
+        let result = if let Some(item) = notice.and_then(&parse) {
+            match sender.try_send(item) {
+                Ok(_sent) => Ok(()),
+                Err(TrySendError::Full(_item)) => {
+                    callback_lagged.fetch_add(1, Ordering::SeqCst);
+                    Err(WebSocketError::SubscriptionLagged)
+                }
+                Err(TrySendError::Closed(_item)) => Ok(())
+            }
+        } else {
+            Ok(())
+        };
+        return Box::pin(async move { result });
+    });
+
+    return (callback, receiver, lagged);
+}
+
+pub struct SubscriptionStream<T> {
+    receiver: Receiver<T>,
+    lagged: Arc<AtomicU64>,
+    failed_block: Arc<Mutex<Option<u32>>>,
+    handle: SubscriptionHandle
+}
+
+impl<T> SubscriptionStream<T> {
+    pub(crate) fn new(receiver: Receiver<T>, lagged: Arc<AtomicU64>, handle: SubscriptionHandle) -> Self {
+        Self {
+            receiver,
+            lagged,
+            failed_block: Arc::new(Mutex::new(None)),
+            handle
+        }
+    }
+
+    pub(crate) fn failed_block_flag(&self) -> Arc<Mutex<Option<u32>>> {
+        return Arc::clone(&self.failed_block);
+    }
+
+    pub fn lagged(&self) -> u64 {
+        return self.lagged.load(Ordering::SeqCst);
+    }
+
+    pub fn failed_block(&self) -> Option<u32> {
+        return *self.failed_block.lock().unwrap();
+    }
+
+    pub fn get_handle(&self) -> &SubscriptionHandle {
+        return &self.handle;
+    }
+
+    pub async fn unsubscribe(self) -> Result<(), GrapheneError> {
+        return self.handle.unsubscribe().await;
+    }
+}
+
+impl<T> Stream for SubscriptionStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        return self.receiver.poll_recv(cx);
+    }
+}
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::fmt;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Copy, Clone)]
 pub enum ChainGetters {
     ChainId,
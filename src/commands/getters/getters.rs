@@ -1,116 +1,747 @@
-use std::rc::Rc;
-use std::cell::RefCell;
-
-use serde::{Deserialize};
-use serde_json::{json};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::pin::Pin;
+use chrono::{Duration, NaiveDateTime};
+use futures::Stream;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::{Arc, RwLock};
 use crate::websocket::{errors::WebSocketError, service::WebSocket};
-use crate::client::apis::GrapheneApi;
-
-#[derive(Debug, Deserialize)]
-struct ChainWebsocketStringResponse {
-    pub id: u8,
-    pub result: String
-}
+use crate::client::apis::{GrapheneApi, GrapheneApis};
+use crate::client::errors::GrapheneError;
+use crate::commands::rpc;
+use crate::config::config::ClientConfig;
+use crate::models::account::{Account, FullAccount};
+use crate::models::asset::{Asset, AssetAmount, AssetDetails, AssetHolder, AssetHolderCount};
+use crate::models::balance::{BalanceObject, VestingBalance};
+use crate::models::block::{BlockHeader, SignedBlock};
+use crate::models::governance::{CommitteeMember, VoteId, Witness, Worker};
+use crate::models::history::{
+    ACCOUNT_HISTORY_PAGE_SIZE, MAX_MARKET_HISTORY_BUCKETS, MAX_TRADE_HISTORY_LIMIT, MarketBucket, MarketTrade, OperationHistory,
+    OperationHistoryDetail
+};
+use crate::models::market::{CallOrder, CollateralBid, ForceSettlement, LimitOrder, MarketTicker, MarketVolume, OrderBook};
+use crate::models::object_id::{
+    AccountId, AssetId, BalanceId, CommitteeMemberId, ObjectId, OperationHistoryId, WitnessId
+};
+use crate::models::objects::ChainObject;
+use crate::models::properties::{ChainConfig, ChainProperties, DynamicGlobalProperties, GlobalProperties};
+use crate::models::transaction::ProcessedTransaction;
 
-#[derive(Debug, Deserialize)]
-struct ChainWebsocketNumberResponse {
-    pub id: u8,
-    pub result: u8
-}
+pub type AccountHistoryStream = Pin<Box<dyn Stream<Item = Result<OperationHistory, GrapheneError>> + Send>>;
 
-pub struct ChainGetter<'a> {
-    ws_service: Option<Rc<RefCell<&'a mut WebSocket<'a>>>>
+#[derive(Clone)]
+pub struct ChainGetter {
+    ws_service: Option<WebSocket>,
+    config: Arc<ClientConfig>,
+    apis: Arc<RwLock<GrapheneApis>>
 } 
 
-impl <'a>ChainGetter<'a> {
+impl Default for ChainGetter {
+    fn default() -> Self {
+        return ChainGetter::new();
+    }
+}
+
+impl ChainGetter {
 
     pub fn new() -> Self {
-        Self { ws_service: None }
+        Self { ws_service: None, config: Arc::new(ClientConfig::default()), apis: Arc::new(RwLock::new(GrapheneApis::new())) }
     }
 
-    pub fn set_ws_connection(&mut self, ws: Rc<RefCell<&'a mut WebSocket<'a>>>) {
+    pub fn set_ws_connection(&mut self, ws: WebSocket) {
         self.ws_service = Some(ws);
     }
 
-    pub async fn get_chain_id(&mut self) -> Result<String, WebSocketError> {
+    pub fn set_config(&mut self, config: Arc<ClientConfig>) {
+        self.config = config;
+    }
+
+    pub fn set_apis(&mut self, apis: Arc<RwLock<GrapheneApis>>) {
+        self.apis = apis;
+    }
 
+    pub async fn call<T: DeserializeOwned, P: Serialize>(&self, api_id: u8, method: &str, params: P) -> Result<T, GrapheneError> {
         if let Some(ws) = &self.ws_service {
+            return rpc::call(ws, api_id, method, params).await;
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+    }
 
-            let req = json!({
-                "method": "call",
-                "params": [0, "get_chain_id", []],
-                "id": 1
-            });
-            
-            let _ = ws.borrow_mut().send(req).await?;
+    pub async fn call_api<T: DeserializeOwned, P: Serialize>(&self, api: GrapheneApi, method: &str, params: P) -> Result<T, GrapheneError> {
+        let known_api_id = self.apis.read().unwrap().lookup(api);
 
-            let result = ws.borrow_mut().receive().await?;
+        let api_id = if let Some(api_id) = known_api_id {
+            api_id
+        } else {
+            let api_id = self.get_chain_api_id(api).await?;
+            self.apis.write().unwrap().set_api(api, api_id);
+            api_id
+        };
 
-            let websocket_response:Result<ChainWebsocketStringResponse,serde_json::Error> = serde_json::from_value(result);
+        return self.call(api_id, method, params).await;
+    }
 
-            if let Ok(response) = websocket_response {
-                return Ok(response.result);
-            } else {
-                return Err(WebSocketError::MessageReceiveError);
-            }
+    pub async fn get_chain_id(&self) -> Result<String, GrapheneError> {
+        return self.call(0, "get_chain_id", ()).await;
+    }
+
+    pub async fn get_chain_properties(&self) -> Result<ChainProperties, GrapheneError> {
+        return self.call(0, "get_chain_properties", ()).await;
+    }
+
+    pub async fn get_global_properties(&self) -> Result<GlobalProperties, GrapheneError> {
+        return self.call(0, "get_global_properties", ()).await;
+    }
 
+    pub async fn get_dynamic_global_properties(&self) -> Result<DynamicGlobalProperties, GrapheneError> {
+        return self.call(0, "get_dynamic_global_properties", ()).await;
+    }
+
+    pub async fn get_config(&self) -> Result<ChainConfig, GrapheneError> {
+        return self.call(0, "get_config", ()).await;
+    }
+
+    pub async fn get_chain_api_id(&self, api: GrapheneApi) -> Result<u8, GrapheneError> {
+        if let Some(ws) = &self.ws_service {
+            let api_name = api.name(&self.config);
+            return rpc::session_call(ws, format!("api:{}", api_name), 1, api_name.as_str(), ()).await;
         } else {
-            return Err(WebSocketError::NotConnected);
+            return Err(WebSocketError::NotConnected.into());
         }
-        
     }
 
-    pub async fn get_chain_api_id(&mut self, api: GrapheneApi) -> Result<u8, WebSocketError> {
+    pub async fn get_block(&self, block_num: u32) -> Result<Option<SignedBlock>, GrapheneError> {
+        return self.call(0, "get_block", (block_num,)).await;
+    }
 
-        if let Some(ws) = &self.ws_service {
+    pub async fn get_block_header(&self, block_num: u32) -> Result<Option<BlockHeader>, GrapheneError> {
+        return self.call(0, "get_block_header", (block_num,)).await;
+    }
 
-            let api_name: String = api.into();
+    pub async fn get_block_header_batch(&self, block_nums: Vec<u32>) -> Result<BTreeMap<u32, Option<BlockHeader>>, GrapheneError> {
+        let headers: Vec<(u32, Option<BlockHeader>)> = self.call(0, "get_block_header_batch", (block_nums,)).await?;
+        return Ok(headers.into_iter().collect());
+    }
 
-            let req = json!({
-                "method": "call",
-                "params": [1, api_name.as_str(), []],
-                "id": 1
-            });
-    
-            let _ = ws.borrow_mut().send(req).await?;
-    
-            let result = ws.borrow_mut().receive().await?;
-    
-            let websocket_response:Result<ChainWebsocketNumberResponse,serde_json::Error> = serde_json::from_value(result);
-    
-            if let Ok(response) = websocket_response {
-                return Ok(response.result);
-            } else {
-                return Err(WebSocketError::MessageReceiveError);
+    pub async fn get_transaction(&self, block_num: u32, trx_in_block: u32) -> Result<ProcessedTransaction, GrapheneError> {
+        return self.call(0, "get_transaction", (block_num, trx_in_block)).await;
+    }
+
+    pub async fn get_recent_transaction_by_id(&self, trx_id: &str) -> Result<Option<ProcessedTransaction>, GrapheneError> {
+        return self.call(0, "get_recent_transaction_by_id", (trx_id,)).await;
+    }
+
+    pub async fn get_objects(&self, objects: Vec<ObjectId>) -> Result<Vec<Option<ChainObject>>, GrapheneError> {
+        return self.call(0, "get_objects", (objects,)).await;
+    }
+
+    pub async fn get_accounts(&self, accounts: Vec<AccountId>) -> Result<Vec<Option<Account>>, GrapheneError> {
+        return self.call(0, "get_accounts", (accounts, false)).await;
+    }
+
+    pub async fn lookup_account_names(&self, names: Vec<String>) -> Result<Vec<Option<Account>>, GrapheneError> {
+        return self.call(0, "lookup_account_names", (names,)).await;
+    }
+
+    pub async fn lookup_accounts(&self, lower_bound_name: &str, limit: u32) -> Result<Vec<(String, AccountId)>, GrapheneError> {
+        return self.call(0, "lookup_accounts", (lower_bound_name, limit, false)).await;
+    }
+
+    pub async fn get_account_by_name(&self, name: &str) -> Result<Option<Account>, GrapheneError> {
+        return self.call(0, "get_account_by_name", (name,)).await;
+    }
+
+    pub async fn get_full_accounts(&self, names_or_ids: Vec<String>) -> Result<Vec<(String, FullAccount)>, GrapheneError> {
+        return self.call(0, "get_full_accounts", (names_or_ids, false)).await;
+    }
+
+    pub async fn get_account_count(&self) -> Result<u64, GrapheneError> {
+        return self.call(0, "get_account_count", ()).await;
+    }
+
+    pub async fn get_key_references(&self, keys: Vec<String>) -> Result<Vec<Vec<AccountId>>, GrapheneError> {
+        return self.call(0, "get_key_references", (keys,)).await;
+    }
+
+    pub async fn get_account_balances(&self, account: AccountId, assets: Vec<AssetId>) -> Result<Vec<AssetAmount>, GrapheneError> {
+        return self.call(0, "get_account_balances", (account, assets)).await;
+    }
+
+    pub async fn get_named_account_balances(&self, name: &str, assets: Vec<AssetId>) -> Result<Vec<AssetAmount>, GrapheneError> {
+        return self.call(0, "get_named_account_balances", (name, assets)).await;
+    }
+
+    pub async fn get_balance_objects(&self, addresses: Vec<String>) -> Result<Vec<BalanceObject>, GrapheneError> {
+        return self.call(0, "get_balance_objects", (addresses,)).await;
+    }
+
+    pub async fn get_vested_balances(&self, balances: Vec<BalanceId>) -> Result<Vec<AssetAmount>, GrapheneError> {
+        return self.call(0, "get_vested_balances", (balances,)).await;
+    }
+
+    pub async fn get_vesting_balances(&self, account: AccountId) -> Result<Vec<VestingBalance>, GrapheneError> {
+        return self.call(0, "get_vesting_balances", (account,)).await;
+    }
+
+    pub async fn get_assets(&self, assets: Vec<AssetId>) -> Result<Vec<Option<Asset>>, GrapheneError> {
+        return self.call(0, "get_assets", (assets, false)).await;
+    }
+
+    pub async fn list_assets(&self, lower_bound_symbol: &str, limit: u32) -> Result<Vec<Asset>, GrapheneError> {
+        return self.call(0, "list_assets", (lower_bound_symbol, limit)).await;
+    }
+
+    pub async fn lookup_asset_symbols(&self, symbols: Vec<String>) -> Result<Vec<Option<Asset>>, GrapheneError> {
+        return self.call(0, "lookup_asset_symbols", (symbols,)).await;
+    }
+
+    pub async fn get_asset_count(&self) -> Result<u64, GrapheneError> {
+        return self.call(0, "get_asset_count", ()).await;
+    }
+
+    pub async fn get_assets_by_issuer(&self, issuer: AccountId, start: AssetId, limit: u32) -> Result<Vec<Asset>, GrapheneError> {
+        return self.call(0, "get_assets_by_issuer", (issuer, start, limit)).await;
+    }
+
+    pub async fn get_asset_details(&self, assets: Vec<AssetId>) -> Result<Vec<Option<AssetDetails>>, GrapheneError> {
+        let assets = self.get_assets(assets).await?;
+
+        let mut object_ids = Vec::new();
+        for asset in assets.iter().flatten() {
+            object_ids.push(ObjectId::from(asset.dynamic_asset_data_id));
+            if let Some(bitasset_data_id) = asset.bitasset_data_id {
+                object_ids.push(bitasset_data_id.into());
             }
+        }
 
-        } else {
-            return Err(WebSocketError::NotConnected);
+        let mut dynamic_data = BTreeMap::new();
+        let mut bitasset_data = BTreeMap::new();
+        for object in self.get_objects(object_ids).await?.into_iter().flatten() {
+            match object {
+                ChainObject::AssetDynamicData(data) => { dynamic_data.insert(data.id, data); }
+                ChainObject::AssetBitassetData(data) => { bitasset_data.insert(data.id, *data); }
+                _ => {}
+            }
         }
-        
+
+        return Ok(assets.into_iter().map(|asset| {
+            let asset = asset?;
+            let dynamic_data = dynamic_data.get(&asset.dynamic_asset_data_id)?.clone();
+            let bitasset_data = asset.bitasset_data_id.and_then(|id| bitasset_data.get(&id).cloned());
+            return Some(AssetDetails { asset, dynamic_data, bitasset_data });
+        }).collect());
     }
 
-    pub async fn get_chain_objects(&mut self, objects: Vec<String>) -> Result<(), WebSocketError> {
+    pub async fn get_asset_holders(&self, asset: AssetId, start: u32, limit: u32) -> Result<Vec<AssetHolder>, GrapheneError> {
+        return self.call_api(GrapheneApi::Asset(0), "get_asset_holders", (asset, start, limit)).await;
+    }
 
-        if let Some(ws) = &self.ws_service {
+    pub async fn get_asset_holders_count(&self, asset: AssetId) -> Result<u64, GrapheneError> {
+        return self.call_api(GrapheneApi::Asset(0), "get_asset_holders_count", (asset,)).await;
+    }
 
-            let req = json!({
-                "method": "call",
-                "params": [0, "get_objects", [objects]],
-                "id": 1
-            });
-    
-            let _ = ws.borrow_mut().send(req).await?;
-    
-            let _result = ws.borrow_mut().receive().await?;
-    
-            return Ok(());
+    pub async fn get_all_asset_holders(&self) -> Result<Vec<AssetHolderCount>, GrapheneError> {
+        return self.call_api(GrapheneApi::Asset(0), "get_all_asset_holders", ()).await;
+    }
 
-        } else {
-            return Err(WebSocketError::NotConnected);
+    pub async fn get_order_book(&self, base: AssetId, quote: AssetId, limit: u32) -> Result<OrderBook, GrapheneError> {
+        return self.call(0, "get_order_book", (base, quote, limit)).await;
+    }
+
+    pub async fn get_limit_orders(&self, base: AssetId, quote: AssetId, limit: u32) -> Result<Vec<LimitOrder>, GrapheneError> {
+        return self.call(0, "get_limit_orders", (base, quote, limit)).await;
+    }
+
+    pub async fn get_call_orders(&self, asset: AssetId, limit: u32) -> Result<Vec<CallOrder>, GrapheneError> {
+        return self.call(0, "get_call_orders", (asset, limit)).await;
+    }
+
+    pub async fn get_settle_orders(&self, asset: AssetId, limit: u32) -> Result<Vec<ForceSettlement>, GrapheneError> {
+        return self.call(0, "get_settle_orders", (asset, limit)).await;
+    }
+
+    pub async fn get_collateral_bids(&self, asset: AssetId, limit: u32, start: u32) -> Result<Vec<CollateralBid>, GrapheneError> {
+        return self.call(0, "get_collateral_bids", (asset, limit, start)).await;
+    }
+
+    pub async fn get_margin_positions(&self, account: AccountId) -> Result<Vec<CallOrder>, GrapheneError> {
+        return self.call(0, "get_margin_positions", (account,)).await;
+    }
+
+    pub async fn get_ticker(&self, base: AssetId, quote: AssetId) -> Result<MarketTicker, GrapheneError> {
+        return self.call(0, "get_ticker", (base, quote)).await;
+    }
+
+    pub async fn get_24_volume(&self, base: AssetId, quote: AssetId) -> Result<MarketVolume, GrapheneError> {
+        return self.call(0, "get_24_volume", (base, quote)).await;
+    }
+
+    pub async fn get_top_markets(&self, limit: u32) -> Result<Vec<MarketVolume>, GrapheneError> {
+        return self.call(0, "get_top_markets", (limit,)).await;
+    }
+
+    pub async fn get_trade_history(&self, base: AssetId, quote: AssetId, start: NaiveDateTime, stop: NaiveDateTime, limit: u32) -> Result<Vec<MarketTrade>, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_trade_history", (base, quote, start, stop, limit)).await;
+    }
+
+    pub async fn get_trade_history_by_sequence(&self, base: AssetId, quote: AssetId, start: i64, stop: NaiveDateTime, limit: u32) -> Result<Vec<MarketTrade>, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_trade_history_by_sequence", (base, quote, start, stop, limit)).await;
+    }
+
+    pub async fn get_market_history(&self, base: AssetId, quote: AssetId, bucket_seconds: u32, start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<MarketBucket>, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_market_history", (base, quote, bucket_seconds, start, end)).await;
+    }
+
+    pub async fn get_market_history_buckets(&self) -> Result<BTreeSet<u32>, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_market_history_buckets", ()).await;
+    }
+
+    pub async fn get_trade_history_range(&self, base: AssetId, quote: AssetId, start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<MarketTrade>, GrapheneError> {
+        let mut trades = self.get_trade_history(base, quote, end, start, MAX_TRADE_HISTORY_LIMIT).await?;
+        let mut page_size = trades.len();
+
+        while page_size == MAX_TRADE_HISTORY_LIMIT as usize {
+            let next_sequence = trades.last().map(|trade| trade.sequence - 1).unwrap_or(0);
+            if next_sequence <= 0 {
+                break;
+            }
+
+            let page = self.get_trade_history_by_sequence(base, quote, next_sequence, start, MAX_TRADE_HISTORY_LIMIT).await?;
+            page_size = page.len();
+            trades.extend(page);
+        }
+
+        return Ok(trades);
+    }
+
+    pub async fn get_market_history_range(&self, base: AssetId, quote: AssetId, bucket_seconds: u32, start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<MarketBucket>, GrapheneError> {
+        let mut buckets: Vec<MarketBucket> = Vec::new();
+        let mut cursor = start;
+
+        while cursor <= end {
+            let page = self.get_market_history(base, quote, bucket_seconds, cursor, end).await?;
+            let page_size = page.len();
+
+            let next_cursor = page.last().map(|bucket| bucket.key.open + Duration::seconds(bucket_seconds as i64));
+            buckets.extend(page);
+
+            if let Some(next_cursor) = next_cursor.filter(|_next| page_size >= MAX_MARKET_HISTORY_BUCKETS) {
+                cursor = next_cursor;
+            } else {
+                break;
+            }
         }
 
+        return Ok(buckets);
+    }
+
+    pub async fn get_account_history(&self, account: AccountId, stop: OperationHistoryId, limit: u32, start: OperationHistoryId) -> Result<Vec<OperationHistory>, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_account_history", (account, stop, limit, start)).await;
+    }
+
+    pub async fn get_relative_account_history(&self, account: AccountId, stop: u64, limit: u32, start: u64) -> Result<Vec<OperationHistory>, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_relative_account_history", (account, stop, limit, start)).await;
+    }
+
+    pub async fn get_account_history_operations(&self, account: AccountId, operation_type: u8, start: OperationHistoryId, stop: OperationHistoryId, limit: u32) -> Result<Vec<OperationHistory>, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_account_history_operations", (account, operation_type, start, stop, limit)).await;
+    }
+
+    pub async fn get_account_history_by_operations(&self, account: AccountId, operation_types: Vec<u8>, start: u32, limit: u32) -> Result<OperationHistoryDetail, GrapheneError> {
+        return self.call_api(GrapheneApi::History(0), "get_account_history_by_operations", (account, operation_types, start, limit)).await;
+    }
+
+    pub fn account_history(&self, account: AccountId) -> AccountHistoryStream {
+        let state = (self.clone(), Some(OperationHistoryId::new(0)), VecDeque::new());
+
+        return Box::pin(futures::stream::unfold(state, move |(getter, mut next_start, mut buffer)| async move {
+            loop {
+                if let Some(operation) = buffer.pop_front() {
+                    return Some((Ok(operation), (getter, next_start, buffer)));
+                }
+
+                let start = next_start?;
+
+                match getter.get_account_history(account, OperationHistoryId::new(0), ACCOUNT_HISTORY_PAGE_SIZE, start).await {
+                    Ok(page) => {
+                        next_start = if page.len() < ACCOUNT_HISTORY_PAGE_SIZE as usize {
+                            None
+                        } else {
+                            page.last()
+                                .map(|operation| operation.id.instance())
+                                .filter(|instance| *instance > 1)
+                                .map(|instance| OperationHistoryId::new(instance - 1))
+                        };
+                        buffer.extend(page);
+                    }
+                    Err(error) => return Some((Err(error), (getter, None, buffer)))
+                }
+            }
+        }));
+    }
+
+    pub async fn get_witnesses(&self, witnesses: Vec<WitnessId>) -> Result<Vec<Option<Witness>>, GrapheneError> {
+        return self.call(0, "get_witnesses", (witnesses,)).await;
+    }
+
+    pub async fn get_witness_by_account(&self, account: AccountId) -> Result<Option<Witness>, GrapheneError> {
+        return self.call(0, "get_witness_by_account", (account,)).await;
+    }
+
+    pub async fn lookup_witness_accounts(&self, lower_bound_name: &str, limit: u32) -> Result<Vec<(String, WitnessId)>, GrapheneError> {
+        return self.call(0, "lookup_witness_accounts", (lower_bound_name, limit)).await;
+    }
+
+    pub async fn get_witness_count(&self) -> Result<u64, GrapheneError> {
+        return self.call(0, "get_witness_count", ()).await;
+    }
+
+    pub async fn get_committee_members(&self, committee_members: Vec<CommitteeMemberId>) -> Result<Vec<Option<CommitteeMember>>, GrapheneError> {
+        return self.call(0, "get_committee_members", (committee_members,)).await;
+    }
+
+    pub async fn get_committee_member_by_account(&self, account: AccountId) -> Result<Option<CommitteeMember>, GrapheneError> {
+        return self.call(0, "get_committee_member_by_account", (account,)).await;
+    }
+
+    pub async fn lookup_committee_member_accounts(&self, lower_bound_name: &str, limit: u32) -> Result<Vec<(String, CommitteeMemberId)>, GrapheneError> {
+        return self.call(0, "lookup_committee_member_accounts", (lower_bound_name, limit)).await;
+    }
+
+    pub async fn get_committee_count(&self) -> Result<u64, GrapheneError> {
+        return self.call(0, "get_committee_count", ()).await;
+    }
+
+    pub async fn get_all_workers(&self, is_expired: Option<bool>) -> Result<Vec<Worker>, GrapheneError> {
+        return self.call(0, "get_all_workers", (is_expired,)).await;
+    }
+
+    pub async fn get_workers_by_account(&self, account: AccountId) -> Result<Vec<Worker>, GrapheneError> {
+        return self.call(0, "get_workers_by_account", (account,)).await;
+    }
+
+    pub async fn lookup_vote_ids(&self, votes: Vec<VoteId>) -> Result<Vec<Option<ChainObject>>, GrapheneError> {
+        return self.call(0, "lookup_vote_ids", (votes,)).await;
     }
 
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::sync::Mutex;
+    use futures::StreamExt;
+    use serde_json::{json, Value};
+    use crate::websocket::interface::IWebSocket;
+
+    type Responder = Box<dyn Fn(&str, &Value) -> Option<Value> + Send + Sync>;
+    type Calls = Arc<Mutex<Vec<(String, Value)>>>;
+
+    struct MockSocket {
+        calls: Calls,
+        responder: Responder
+    }
+
+    impl IWebSocket for MockSocket {
+        fn connect(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Ok(()) });
+        }
+
+        fn send(&self, _msg: Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Ok(()) });
+        }
+
+        fn receive(&self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Err(WebSocketError::MessageReceiveError) });
+        }
+
+        fn call(&self, msg: Value) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+            let method = msg.pointer("/params/1").and_then(|method| method.as_str()).unwrap_or("").to_string();
+            let params = msg.pointer("/params/2").cloned().unwrap_or(Value::Null);
+            let response = if method == "history" {
+                Some(json!(3))
+            } else {
+                (self.responder)(&method, &params)
+            };
+
+            self.calls.lock().unwrap().push((method, params));
+
+            return Box::pin(async move {
+                if let Some(result) = response {
+                    return Ok(json!({"id": 1, "result": result}));
+                } else {
+                    return Ok(json!({"id": 1, "error": {"code": 1, "message": "rejected"}}));
+                }
+            });
+        }
+
+        fn add_session_request(&self, _key: String, _request: Value, _result: Option<Value>) {}
+
+        fn remove_session_request(&self, _key: &str) {}
+
+        fn close(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+            return Box::pin(async { Ok(()) });
+        }
+    }
+
+    fn mock_getter<F: Fn(&str, &Value) -> Option<Value> + Send + Sync + 'static>(responder: F) -> (ChainGetter, Calls) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut getter = ChainGetter::new();
+        getter.set_ws_connection(WebSocket::new(MockSocket { calls: Arc::clone(&calls), responder: Box::new(responder) }));
+        return (getter, calls);
+    }
+
+    fn calls_to(calls: &Calls, method: &str) -> Vec<Value> {
+        return calls.lock().unwrap().iter()
+            .filter(|(called, _params)| called == method)
+            .map(|(_method, params)| params.clone())
+            .collect();
+    }
+
+    fn time(text: &str) -> NaiveDateTime {
+        return NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S").unwrap();
+    }
+
+    fn trades(sequences: impl Iterator<Item = i64>) -> Value {
+        return Value::Array(sequences.map(|sequence| json!({
+            "sequence": sequence,
+            "date": "2021-11-01T12:00:00",
+            "price": "0.0400",
+            "amount": "100.00000",
+            "value": "4.0000",
+            "type": "buy",
+            "side1_account_id": "1.2.100",
+            "side2_account_id": "1.2.200"
+        })).collect());
+    }
+
+    fn buckets(open: NaiveDateTime, seconds: u32, count: usize) -> Value {
+        return Value::Array((0..count).map(|index| json!({
+            "id": format!("5.1.{}", index),
+            "key": {
+                "base": "1.3.0",
+                "quote": "1.3.113",
+                "seconds": seconds,
+                "open": open + Duration::seconds(seconds as i64 * index as i64)
+            },
+            "high_base": 250000,
+            "high_quote": 10000,
+            "low_base": "240000",
+            "low_quote": "10000",
+            "open_base": 245000,
+            "open_quote": 10000,
+            "close_base": 248000,
+            "close_quote": 10000,
+            "base_volume": "98000000",
+            "quote_volume": 4000000
+        })).collect());
+    }
+
+    fn operations(instances: impl Iterator<Item = u64>) -> Value {
+        return Value::Array(instances.map(|instance| json!({
+            "id": format!("1.11.{}", instance),
+            "op": [0, {
+                "fee": {"amount": 86869, "asset_id": "1.3.0"},
+                "from": "1.2.100",
+                "to": "1.2.200",
+                "amount": {"amount": 100000, "asset_id": "1.3.0"},
+                "extensions": []
+            }],
+            "result": [0, {}],
+            "block_num": 62000000 + instance,
+            "trx_in_block": 0,
+            "op_in_trx": 0,
+            "virtual_op": 0
+        })).collect());
+    }
+
+    fn history_page(params: &Value) -> Option<Value> {
+        let start = params[3].as_str()?.parse::<OperationHistoryId>().ok()?.instance();
+        let newest = if start == 0 { 350 } else { start };
+        let lowest = newest.saturating_sub(ACCOUNT_HISTORY_PAGE_SIZE as u64 - 1).max(1);
+        return Some(operations((lowest..=newest).rev()));
+    }
+
+    #[tokio::test]
+    async fn account_history_walks_backwards_in_pages() {
+        let (getter, calls) = mock_getter(|method, params| {
+            if method == "get_account_history" {
+                return history_page(params);
+            } else {
+                return None;
+            }
+        });
+
+        let history: Vec<OperationHistory> = getter.account_history(AccountId::new(100))
+            .map(|operation| operation.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(history.len(), 350);
+        assert_eq!(history.first().map(|operation| operation.id), Some(OperationHistoryId::new(350)));
+        assert_eq!(history.last().map(|operation| operation.id), Some(OperationHistoryId::new(1)));
+        assert_eq!(calls_to(&calls, "get_account_history"), vec![
+            json!(["1.2.100", "1.11.0", 100, "1.11.0"]),
+            json!(["1.2.100", "1.11.0", 100, "1.11.250"]),
+            json!(["1.2.100", "1.11.0", 100, "1.11.150"]),
+            json!(["1.2.100", "1.11.0", 100, "1.11.50"])
+        ]);
+    }
+
+    #[tokio::test]
+    async fn account_history_stops_after_full_page_ending_at_first_operation() {
+        let (getter, calls) = mock_getter(|method, _params| {
+            if method == "get_account_history" {
+                return Some(operations((1..=100).rev()));
+            } else {
+                return None;
+            }
+        });
+
+        let history: Vec<_> = getter.account_history(AccountId::new(100)).collect().await;
+
+        assert_eq!(history.len(), 100);
+        assert_eq!(calls_to(&calls, "get_account_history").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn account_history_ends_after_an_error() {
+        let (getter, calls) = mock_getter(|method, params| {
+            if method == "get_account_history" && params[3] == json!("1.11.0") {
+                return history_page(params);
+            } else {
+                return None;
+            }
+        });
+
+        let history: Vec<_> = getter.account_history(AccountId::new(100)).collect().await;
+
+        assert_eq!(history.len(), 101);
+        assert!(history[..100].iter().all(|operation| operation.is_ok()));
+        assert!(matches!(history[100], Err(GrapheneError::Rpc(_))));
+        assert_eq!(calls_to(&calls, "get_account_history").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn trade_history_range_pages_by_sequence() {
+        let (getter, calls) = mock_getter(|method, params| {
+            match method {
+                "get_trade_history" => return Some(trades((151..=250).rev())),
+                "get_trade_history_by_sequence" => {
+                    let start = params[2].as_i64().unwrap();
+                    if start > 100 {
+                        return Some(trades((start - 99..=start).rev()));
+                    } else {
+                        return Some(trades((31..=start).rev()));
+                    }
+                }
+                _ => return None
+            }
+        });
+        let start = time("2021-11-01T00:00:00");
+        let end = time("2021-11-02T00:00:00");
+
+        let result = getter.get_trade_history_range(AssetId::new(0), AssetId::new(113), start, end).await.unwrap();
+
+        assert_eq!(result.len(), 220);
+        assert_eq!(result.first().map(|trade| trade.sequence), Some(250));
+        assert_eq!(result.last().map(|trade| trade.sequence), Some(31));
+        assert_eq!(calls_to(&calls, "get_trade_history"), vec![json!(["1.3.0", "1.3.113", "2021-11-02T00:00:00", "2021-11-01T00:00:00", 100])]);
+        assert_eq!(calls_to(&calls, "get_trade_history_by_sequence"), vec![
+            json!(["1.3.0", "1.3.113", 150, "2021-11-01T00:00:00", 100]),
+            json!(["1.3.0", "1.3.113", 50, "2021-11-01T00:00:00", 100])
+        ]);
+    }
+
+    #[tokio::test]
+    async fn trade_history_range_stops_at_first_sequence() {
+        let (getter, calls) = mock_getter(|method, _params| {
+            if method == "get_trade_history" {
+                return Some(trades((1..=100).rev()));
+            } else {
+                return None;
+            }
+        });
+
+        let result = getter.get_trade_history_range(AssetId::new(0), AssetId::new(113), time("2021-11-01T00:00:00"), time("2021-11-02T00:00:00")).await.unwrap();
+
+        assert_eq!(result.len(), 100);
+        assert!(calls_to(&calls, "get_trade_history_by_sequence").is_empty());
+    }
+
+    #[tokio::test]
+    async fn market_history_range_continues_after_full_pages() {
+        let (getter, calls) = mock_getter(|method, params| {
+            if method == "get_market_history" {
+                let cursor = time(params[3].as_str().unwrap());
+                let count = if cursor == time("2021-01-01T00:00:00") { MAX_MARKET_HISTORY_BUCKETS } else { 37 };
+                return Some(buckets(cursor, 3600, count));
+            } else {
+                return None;
+            }
+        });
+
+        let result = getter.get_market_history_range(AssetId::new(0), AssetId::new(113), 3600, time("2021-01-01T00:00:00"), time("2021-02-01T00:00:00")).await.unwrap();
+
+        assert_eq!(result.len(), 237);
+        assert_eq!(result[200].key.open, time("2021-01-09T08:00:00"));
+        assert_eq!(calls_to(&calls, "get_market_history"), vec![
+            json!(["1.3.0", "1.3.113", 3600, "2021-01-01T00:00:00", "2021-02-01T00:00:00"]),
+            json!(["1.3.0", "1.3.113", 3600, "2021-01-09T08:00:00", "2021-02-01T00:00:00"])
+        ]);
+    }
+
+    #[tokio::test]
+    async fn market_history_range_stops_below_bucket_limit() {
+        let (getter, calls) = mock_getter(|method, params| {
+            if method == "get_market_history" {
+                return Some(buckets(time(params[3].as_str().unwrap()), 86400, MAX_MARKET_HISTORY_BUCKETS - 1));
+            } else {
+                return None;
+            }
+        });
+
+        let result = getter.get_market_history_range(AssetId::new(0), AssetId::new(113), 86400, time("2021-01-01T00:00:00"), time("2022-01-01T00:00:00")).await.unwrap();
+
+        assert_eq!(result.len(), 199);
+        assert_eq!(calls_to(&calls, "get_market_history").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn market_history_range_propagates_errors() {
+        let (getter, _calls) = mock_getter(|_method, _params| None);
+
+        let result = getter.get_market_history_range(AssetId::new(0), AssetId::new(113), 3600, time("2021-01-01T00:00:00"), time("2021-02-01T00:00:00")).await;
+
+        assert!(matches!(result, Err(GrapheneError::Rpc(_))));
+    }
+
+    #[tokio::test]
+    async fn lookup_vote_ids_sends_string_ids_and_types_results() {
+        let (getter, calls) = mock_getter(|method, _params| {
+            if method == "lookup_vote_ids" {
+                return Some(json!([
+                    {
+                        "id": "1.6.69",
+                        "witness_account": "1.2.1234",
+                        "signing_key": "BTS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV",
+                        "vote_id": "1:210",
+                        "total_votes": "61395773409245"
+                    },
+                    null
+                ]));
+            } else {
+                return None;
+            }
+        });
+
+        let votes = vec!["1:210".parse::<VoteId>().unwrap(), "2:999".parse::<VoteId>().unwrap()];
+        let result = getter.lookup_vote_ids(votes).await.unwrap();
+
+        assert_eq!(calls_to(&calls, "lookup_vote_ids"), vec![json!([["1:210", "2:999"]])]);
+        assert!(matches!(&result[0], Some(ChainObject::Witness(witness)) if witness.id == WitnessId::new(69)));
+        assert!(result[1].is_none());
+    }
+}
\ No newline at end of file
@@ -1,2 +1,3 @@
 pub mod getters;
-pub mod subscriptions;
\ No newline at end of file
+pub mod subscriptions;
+pub mod rpc;
\ No newline at end of file
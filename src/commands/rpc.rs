@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use crate::websocket::service::WebSocket;
+use crate::client::errors::{GrapheneError, RpcError};
+
+#[derive(Debug, Deserialize)]
+struct ChainWebsocketResponse {
+    #[serde(default)]
+    pub result: Value,
+    pub error: Option<Value>
+}
+
+pub fn parse_chain_response<T: DeserializeOwned>(response: Value) -> Result<T, GrapheneError> {
+
+    let websocket_response:ChainWebsocketResponse = serde_json::from_value(response)
+        .map_err(|e| GrapheneError::Serialization(e.to_string()))?;
+
+    if let Some(error) = websocket_response.error {
+        return Err(GrapheneError::Rpc(RpcError::from_value(error)));
+    }
+
+    return serde_json::from_value(websocket_response.result)
+        .map_err(|e| GrapheneError::Serialization(e.to_string()));
+}
+
+pub fn build_request<P: Serialize>(api_id: u8, method: &str, params: P) -> Result<Value, GrapheneError> {
+
+    let params_json = match serde_json::to_value(params).map_err(|e| GrapheneError::Serialization(e.to_string()))? {
+        Value::Null => json!([]),
+        Value::Array(params) => Value::Array(params),
+        Value::Object(_params) => {
+            return Err(GrapheneError::Serialization(format!("Params for {} must serialize as a positional sequence, not an object", method)));
+        }
+        param => json!([param])
+    };
+
+    return Ok(json!({
+        "method": "call",
+        "params": [api_id, method, params_json]
+    }));
+}
+
+pub async fn call<T: DeserializeOwned, P: Serialize>(
+    ws: &WebSocket,
+    api_id: u8,
+    method: &str,
+    params: P
+) -> Result<T, GrapheneError> {
+
+    let req = build_request(api_id, method, params)?;
+
+    let result = ws.call(req).await?;
+
+    return parse_chain_response(result);
+}
+
+pub async fn session_call<T: DeserializeOwned, P: Serialize>(
+    ws: &WebSocket,
+    key: String,
+    api_id: u8,
+    method: &str,
+    params: P
+) -> Result<T, GrapheneError> {
+
+    let req = build_request(api_id, method, params)?;
+
+    let result = ws.call(req.clone()).await?;
+
+    let response: T = parse_chain_response(result.clone())?;
+
+    ws.add_session_request(key, req, result.get("result").cloned());
+
+    return Ok(response);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct NamedParams {
+        account: String,
+        limit: u32
+    }
+
+    #[test]
+    fn build_request_keeps_tuple_params_positional() {
+        let request = build_request(0, "get_accounts", (vec!["1.2.0"], false)).unwrap();
+        assert_eq!(request, json!({"method": "call", "params": [0, "get_accounts", [["1.2.0"], false]]}));
+    }
+
+    #[test]
+    fn build_request_maps_unit_to_empty_params() {
+        let request = build_request(0, "get_chain_id", ()).unwrap();
+        assert_eq!(request["params"][2], json!([]));
+    }
+
+    #[test]
+    fn build_request_wraps_single_param() {
+        let request = build_request(0, "get_block", 42u32).unwrap();
+        assert_eq!(request["params"][2], json!([42]));
+    }
+
+    #[test]
+    fn build_request_rejects_struct_params() {
+        let params = NamedParams { account: String::from("1.2.0"), limit: 10 };
+        assert!(matches!(build_request(0, "get_account_history", params), Err(GrapheneError::Serialization(_))));
+    }
+}
@@ -1,6 +1,11 @@
+use std::collections::HashSet;
 use serde_json::Value;
 use serde::Deserialize;
 
+use crate::models::object_id::{AccountId, ObjectId};
+use crate::models::objects::ChainObject;
+use crate::models::transaction::SignedTransaction;
+
 #[derive(Debug, Deserialize)]
 pub struct ChainResponse {
     method: String,
@@ -15,7 +20,7 @@ impl ChainResponse {
 
     fn get_callback_id(&self) -> Option<u64> {
 
-        if let Some(callback_id) = self.params.get(0).and_then(|cb_id| if let Some(id) = cb_id.as_u64() { Some(id) } else { None }) {
+        if let Some(callback_id) = self.params.first().and_then(|cb_id| cb_id.as_u64()) {
             return Some(callback_id);
         } else {
             return None;
@@ -50,4 +55,101 @@ impl ChainResponse {
         return Some((cb_id,cb_response_body));
 
     }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AccountUpdate {
+    pub updated: Vec<ChainObject>,
+    pub removed: Vec<ObjectId>
+}
+
+impl AccountUpdate {
+    pub fn from_notice(notice: Value) -> Option<AccountUpdate> {
+        let mut update = AccountUpdate::default();
+
+        let entries = notice.as_array()?;
+
+        for entry in entries {
+            let objects = if let Some(objects) = entry.as_array() {
+                objects.clone()
+            } else {
+                vec![entry.clone()]
+            };
+
+            for object in objects {
+                if let Some(removed_id) = object.as_str().and_then(|id| id.parse::<ObjectId>().ok()) {
+                    update.removed.push(removed_id);
+                } else if object.is_object() {
+                    update.updated.push(ChainObject::from_value(object));
+                }
+            }
+        }
+
+        return Some(update);
+    }
+}
+
+const ACCOUNT_FIELDS: [&str; 5] = ["owner", "account", "seller", "borrower", "bidder"];
+
+fn watches_account(value: Option<&Value>, accounts: &[AccountId]) -> bool {
+    return value.and_then(|value| value.as_str())
+        .and_then(|id| id.parse::<AccountId>().ok())
+        .map(|account| accounts.contains(&account))
+        .unwrap_or(false);
+}
+
+fn keep_account_object(object: &Value, accounts: &[AccountId], seen: &mut HashSet<ObjectId>) -> bool {
+    if let Some(id) = object.as_str().and_then(|id| id.parse::<ObjectId>().ok()) {
+        return watches_account(Some(object), accounts) || seen.remove(&id);
+    }
+
+    if !object.is_object() {
+        return false;
+    }
+
+    let related = watches_account(object.get("id"), accounts)
+        || ACCOUNT_FIELDS.iter().any(|field| watches_account(object.get(*field), accounts));
+
+    if related {
+        if let Some(id) = object.get("id").and_then(|id| id.as_str()).and_then(|id| id.parse::<ObjectId>().ok()) {
+            seen.insert(id);
+        }
+    }
+
+    return related;
+}
+
+pub(crate) fn filter_account_notice(notice: &Value, accounts: &[AccountId], seen: &mut HashSet<ObjectId>) -> Option<Value> {
+    let mut filtered = Vec::new();
+
+    for entry in notice.as_array()? {
+        if let Some(objects) = entry.as_array() {
+            let objects: Vec<Value> = objects.iter()
+                .filter(|object| keep_account_object(object, accounts, seen))
+                .cloned()
+                .collect();
+
+            if !objects.is_empty() {
+                filtered.push(Value::Array(objects));
+            }
+        } else if keep_account_object(entry, accounts, seen) {
+            filtered.push(entry.clone());
+        }
+    }
+
+    if filtered.is_empty() {
+        return None;
+    }
+
+    return Some(Value::Array(filtered));
+}
+
+pub fn parse_pending_transaction(notice: Value) -> Option<SignedTransaction> {
+    let transaction = if let Some(params) = notice.as_array() {
+        params.first()?.clone()
+    } else {
+        notice
+    };
+
+    return serde_json::from_value(transaction).ok();
 }
\ No newline at end of file
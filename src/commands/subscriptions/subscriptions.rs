@@ -1,14 +1,21 @@
-use std::rc::Rc;
-use std::cell::RefCell;
-
-use serde_json::{json};
+use serde_json::Value;
 use crate::websocket::{errors::WebSocketError, service::WebSocket};
+use crate::client::errors::GrapheneError;
+use crate::commands::rpc;
+use crate::models::object_id::{AccountId, AssetId};
 
-pub struct ChainSubscriptions<'a> {
-    ws_service: Option<Rc<RefCell<&'a mut WebSocket<'a>>>>
+#[derive(Clone)]
+pub struct ChainSubscriptions {
+    ws_service: Option<WebSocket>
 } 
 
-impl <'a>ChainSubscriptions<'a> {
+impl Default for ChainSubscriptions {
+    fn default() -> Self {
+        return ChainSubscriptions::new();
+    }
+}
+
+impl ChainSubscriptions {
 
     pub fn new() -> Self {
         Self {
@@ -16,36 +23,136 @@ impl <'a>ChainSubscriptions<'a> {
         }
     }
 
-    pub fn set_ws_connection(&mut self, ws: Rc<RefCell<&'a mut WebSocket<'a>>>) {
+    pub fn set_ws_connection(&mut self, ws: WebSocket) {
         self.ws_service = Some(ws);
     }
 
-    pub async fn get_full_accounts(&mut self, cb_id: u64, accounts: Vec<String>) -> Result<(), WebSocketError> {
+    pub async fn set_subscribe_callback(&self, cb_id: u64) -> Result<(), GrapheneError> {
 
         if let Some(ws) = &self.ws_service {
 
-            let req = json!({
-                "method": "call",
-                "params": [0, "set_subscribe_callback", [cb_id, true]],
-                "id": 1
-            });
-    
-            let _ = ws.borrow_mut().send(req).await?;
+            let _result: Value = rpc::session_call(ws, format!("subscription:{}:callback", cb_id), 0, "set_subscribe_callback", (cb_id, true)).await?;
 
-            let req = json!({
-                "method": "call",
-                "params": [0, "get_objects", [accounts]],
-                "id": 1
-            });
-    
-            let _ = ws.borrow_mut().send(req).await?;
+            return Ok(());
+
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+    }
+
+    pub async fn subscribe_to_accounts(&self, cb_id: u64, accounts: Vec<AccountId>) -> Result<(), GrapheneError> {
+
+        if let Some(ws) = &self.ws_service {
+
+            let _result: Value = rpc::session_call(ws, format!("subscription:{}:objects", cb_id), 0, "get_objects", (accounts,)).await?;
     
             return Ok(());
 
         } else {
-            return Err(WebSocketError::NotConnected);
+            return Err(WebSocketError::NotConnected.into());
         }
         
     }
 
+    pub async fn set_block_applied_callback(&self, cb_id: u64) -> Result<(), GrapheneError> {
+
+        if let Some(ws) = &self.ws_service {
+
+            let _result: Value = rpc::session_call(ws, format!("subscription:{}:blocks", cb_id), 0, "set_block_applied_callback", (cb_id,)).await?;
+
+            return Ok(());
+
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+    }
+
+    pub async fn set_pending_transaction_callback(&self, cb_id: u64) -> Result<(), GrapheneError> {
+
+        if let Some(ws) = &self.ws_service {
+
+            let _result: Value = rpc::session_call(ws, format!("subscription:{}:pending", cb_id), 0, "set_pending_transaction_callback", (cb_id,)).await?;
+
+            return Ok(());
+
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+    }
+
+    pub async fn subscribe_to_market(&self, cb_id: u64, base: AssetId, quote: AssetId) -> Result<(), GrapheneError> {
+
+        if let Some(ws) = &self.ws_service {
+
+            let _result: Value = rpc::session_call(ws, format!("subscription:{}:market", cb_id), 0, "subscribe_to_market", (cb_id, base, quote)).await?;
+
+            return Ok(());
+
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+    }
+
+    pub async fn unsubscribe_from_market(&self, cb_id: u64, base: AssetId, quote: AssetId) -> Result<(), GrapheneError> {
+
+        if let Some(ws) = &self.ws_service {
+
+            let _result: Value = rpc::call(ws, 0, "unsubscribe_from_market", (base, quote)).await?;
+
+            ChainSubscriptions::remove_subscription_session(ws, cb_id);
+
+            return Ok(());
+
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+    }
+
+    fn remove_subscription_session(ws: &WebSocket, cb_id: u64) {
+        for request in ["callback", "objects", "blocks", "pending", "market"] {
+            ws.remove_session_request(&format!("subscription:{}:{}", cb_id, request));
+        }
+    }
+
+    pub async fn unsubscribe_from_accounts(&self, cb_id: u64, accounts: Vec<AccountId>) -> Result<(), GrapheneError> {
+
+        if let Some(ws) = &self.ws_service {
+
+            if !accounts.is_empty() {
+                let _result: Value = rpc::call(ws, 0, "unsubscribe_from_accounts", (accounts,)).await?;
+            }
+
+            ChainSubscriptions::remove_subscription_session(ws, cb_id);
+
+            return Ok(());
+
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+    }
+
+    pub async fn cancel_all_subscriptions(&self, cb_ids: Vec<u64>) -> Result<(), GrapheneError> {
+
+        if let Some(ws) = &self.ws_service {
+
+            let _result: Value = rpc::call(ws, 0, "cancel_all_subscriptions", ()).await?;
+
+            for cb_id in cb_ids {
+                ChainSubscriptions::remove_subscription_session(ws, cb_id);
+            }
+
+            return Ok(());
+
+        } else {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+    }
+
 }
\ No newline at end of file
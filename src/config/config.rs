@@ -1,33 +1,112 @@
+use std::env;
 use std::fs::File;
 use std::io::Read;
+use std::path::{Path, PathBuf};
 use serde::Deserialize;
-use lazy_static::lazy_static;
-use std::iter::FromIterator;
 
+use crate::config::errors::ConfigError;
 use crate::websocket::implementations::timeouts::Timeout;
 
-lazy_static! {
-    pub static ref CONFIG: Config = {
-        // Open the file
-        let file_path = "/home/cesar/Projects/grapheners/config.json";
-        
-        let mut file = File::open(file_path).expect("Unable to open config file");
-        // Read the file contents into a string
-        let mut file_contents = String::new();
-        file.read_to_string(&mut file_contents)
-            .expect("Unable to read config file");
-        // Deserialize the JSON data into your config struct
-        serde_json::from_str(&file_contents).expect("Unable to parse config JSON")
-    };
-}
+pub const ENV_PREFIX: &str = "GRAPHENERS_";
 
-#[derive(Debug, Deserialize)]
-pub struct Config {
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ClientConfig {
     timeouts: Timeouts,
-    custom_apis: Vec<CustomApi>
+    custom_apis: Vec<CustomApi>,
+    subscription_buffer: usize,
+    heartbeat_max_missed: u32,
+    reconnect_max_attempts: u32
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            timeouts: Timeouts::default(),
+            custom_apis: Vec::new(),
+            subscription_buffer: 100,
+            heartbeat_max_missed: 3,
+            reconnect_max_attempts: 10
+        }
+    }
 }
 
-impl Config {
+impl ClientConfig {
+    pub fn builder() -> ClientConfigBuilder {
+        return ClientConfigBuilder::new();
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+
+        let mut file = File::open(path)
+            .map_err(|e| ConfigError::Io(path.display().to_string(), e.to_string()))?;
+
+        let mut file_contents = String::new();
+        file.read_to_string(&mut file_contents)
+            .map_err(|e| ConfigError::Io(path.display().to_string(), e.to_string()))?;
+
+        return serde_json::from_str(&file_contents)
+            .map_err(|e| ConfigError::Parse(path.display().to_string(), e.to_string()));
+    }
+
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut config = ClientConfig::default();
+        config.apply_env()?;
+        return Ok(config);
+    }
+
+    fn apply_env(&mut self) -> Result<(), ConfigError> {
+        for timeout in Timeout::all() {
+            let key = env_key(timeout.key());
+            if let Ok(value) = env::var(&key) {
+                let millis = value.trim().parse::<u64>()
+                    .map_err(|_e| ConfigError::InvalidEnv(key.clone(), value.clone()))?;
+                self.set_timeout(timeout, millis);
+            }
+        }
+
+        let key = env_key("subscription_buffer");
+        if let Ok(value) = env::var(&key) {
+            let capacity = value.trim().parse::<usize>()
+                .ok()
+                .filter(|capacity| *capacity > 0)
+                .ok_or(ConfigError::InvalidEnv(key.clone(), value.clone()))?;
+            self.set_subscription_buffer(capacity);
+        }
+
+        let key = env_key("heartbeat_max_missed");
+        if let Ok(value) = env::var(&key) {
+            let max_missed = value.trim().parse::<u32>()
+                .ok()
+                .filter(|max_missed| *max_missed > 0)
+                .ok_or(ConfigError::InvalidEnv(key.clone(), value.clone()))?;
+            self.set_heartbeat_max_missed(max_missed);
+        }
+
+        let key = env_key("reconnect_max_attempts");
+        if let Ok(value) = env::var(&key) {
+            let max_attempts = value.trim().parse::<u32>()
+                .map_err(|_e| ConfigError::InvalidEnv(key.clone(), value.clone()))?;
+            self.set_reconnect_max_attempts(max_attempts);
+        }
+
+        let key = env_key("custom_apis");
+        if let Ok(value) = env::var(&key) {
+            for entry in value.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+                if let Some((id, name)) = entry.split_once(':') {
+                    let id = id.trim().parse::<u8>()
+                        .map_err(|_e| ConfigError::InvalidEnv(key.clone(), value.clone()))?;
+                    self.set_custom_api(id, String::from(name.trim()));
+                } else {
+                    return Err(ConfigError::InvalidEnv(key, value));
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
     pub fn get_custom_api_name(&self, id:u8) -> String {
         return self.custom_apis
             .iter()
@@ -39,6 +118,132 @@ impl Config {
     pub fn get_timeout(&self, timeout: Timeout) -> u64 {
         return self.timeouts.get_timeout(timeout);
     }
+
+    pub fn set_timeout(&mut self, timeout: Timeout, millis: u64) {
+        self.timeouts.set_timeout(timeout, millis);
+    }
+
+    pub fn get_subscription_buffer(&self) -> usize {
+        return std::cmp::max(self.subscription_buffer, 1);
+    }
+
+    pub fn set_subscription_buffer(&mut self, capacity: usize) {
+        self.subscription_buffer = std::cmp::max(capacity, 1);
+    }
+
+    pub fn get_heartbeat_max_missed(&self) -> u32 {
+        return std::cmp::max(self.heartbeat_max_missed, 1);
+    }
+
+    pub fn set_heartbeat_max_missed(&mut self, max_missed: u32) {
+        self.heartbeat_max_missed = std::cmp::max(max_missed, 1);
+    }
+
+    pub fn get_reconnect_max_attempts(&self) -> u32 {
+        return self.reconnect_max_attempts;
+    }
+
+    pub fn set_reconnect_max_attempts(&mut self, max_attempts: u32) {
+        self.reconnect_max_attempts = max_attempts;
+    }
+
+    pub fn set_custom_api(&mut self, id: u8, name: String) {
+        if let Some(existing) = self.custom_apis.iter_mut().find(|api| api.id == id) {
+            existing.name = name;
+        } else {
+            self.custom_apis.push(CustomApi { id, name });
+        }
+    }
+}
+
+fn env_key(key: &str) -> String {
+    return format!("{}{}", ENV_PREFIX, key.to_uppercase());
+}
+
+#[derive(Debug, Default)]
+pub struct ClientConfigBuilder {
+    file: Option<PathBuf>,
+    env: bool,
+    timeouts: Vec<(Timeout, u64)>,
+    custom_apis: Vec<CustomApi>,
+    subscription_buffer: Option<usize>,
+    heartbeat_max_missed: Option<u32>,
+    reconnect_max_attempts: Option<u32>
+}
+
+impl ClientConfigBuilder {
+    pub fn new() -> Self {
+        return ClientConfigBuilder::default();
+    }
+
+    pub fn file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.file = Some(path.as_ref().to_path_buf());
+        return self;
+    }
+
+    pub fn env(mut self) -> Self {
+        self.env = true;
+        return self;
+    }
+
+    pub fn timeout(mut self, timeout: Timeout, millis: u64) -> Self {
+        self.timeouts.push((timeout, millis));
+        return self;
+    }
+
+    pub fn subscription_buffer(mut self, capacity: usize) -> Self {
+        self.subscription_buffer = Some(capacity);
+        return self;
+    }
+
+    pub fn heartbeat_max_missed(mut self, max_missed: u32) -> Self {
+        self.heartbeat_max_missed = Some(max_missed);
+        return self;
+    }
+
+    pub fn reconnect_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.reconnect_max_attempts = Some(max_attempts);
+        return self;
+    }
+
+    pub fn custom_api(mut self, id: u8, name: &str) -> Self {
+        self.custom_apis.push(CustomApi { id, name: String::from(name) });
+        return self;
+    }
+
+    pub fn build(self) -> Result<ClientConfig, ConfigError> {
+        let mut config = if let Some(path) = &self.file {
+            ClientConfig::from_file(path)?
+        } else {
+            ClientConfig::default()
+        };
+
+        if self.env {
+            config.apply_env()?;
+        }
+
+        for (timeout, millis) in self.timeouts {
+            config.set_timeout(timeout, millis);
+        }
+
+        for api in self.custom_apis {
+            config.set_custom_api(api.id, api.name);
+        }
+
+        if let Some(capacity) = self.subscription_buffer {
+            config.set_subscription_buffer(capacity);
+        }
+
+        if let Some(max_missed) = self.heartbeat_max_missed {
+            config.set_heartbeat_max_missed(max_missed);
+        }
+
+        if let Some(max_attempts) = self.reconnect_max_attempts {
+            config.set_reconnect_max_attempts(max_attempts);
+        }
+
+        return Ok(config);
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -47,17 +252,36 @@ struct CustomApi {
     pub name: String
 }
 
-impl<'a> FromIterator<&'a CustomApi> for Vec<CustomApi> {
-    fn from_iter<I: IntoIterator<Item = &'a CustomApi>>(iter: I) -> Self {
-        iter.into_iter().cloned().collect()
-    }
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
 struct Timeouts {
     ws_send: u64,
     ws_receive: u64,
-    ws_close: u64
+    ws_close: u64,
+    ws_reconnect_min: u64,
+    ws_reconnect_max: u64,
+    pool_health_check: u64,
+    pool_max_head_age: u64,
+    ws_heartbeat: u64,
+    block_fetch_min: u64,
+    block_fetch_max: u64
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            ws_send: 1000,
+            ws_receive: 1000,
+            ws_close: 100,
+            ws_reconnect_min: 500,
+            ws_reconnect_max: 30000,
+            pool_health_check: 30000,
+            pool_max_head_age: 60000,
+            ws_heartbeat: 10000,
+            block_fetch_min: 250,
+            block_fetch_max: 4000
+        }
+    }
 }
 
 impl Timeouts {
@@ -65,7 +289,105 @@ impl Timeouts {
         match timeout {
             Timeout::WebSocketSend => self.ws_send,
             Timeout::WebSocketReceive => self.ws_receive,
-            Timeout::WebsocketClose => self.ws_close
+            Timeout::WebsocketClose => self.ws_close,
+            Timeout::ReconnectMin => self.ws_reconnect_min,
+            Timeout::ReconnectMax => self.ws_reconnect_max,
+            Timeout::PoolHealthCheck => self.pool_health_check,
+            Timeout::PoolMaxHeadAge => self.pool_max_head_age,
+            Timeout::Heartbeat => self.ws_heartbeat,
+            Timeout::BlockFetchMin => self.block_fetch_min,
+            Timeout::BlockFetchMax => self.block_fetch_max
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: Timeout, millis: u64) {
+        match timeout {
+            Timeout::WebSocketSend => self.ws_send = millis,
+            Timeout::WebSocketReceive => self.ws_receive = millis,
+            Timeout::WebsocketClose => self.ws_close = millis,
+            Timeout::ReconnectMin => self.ws_reconnect_min = millis,
+            Timeout::ReconnectMax => self.ws_reconnect_max = millis,
+            Timeout::PoolHealthCheck => self.pool_health_check = millis,
+            Timeout::PoolMaxHeadAge => self.pool_max_head_age = millis,
+            Timeout::Heartbeat => self.ws_heartbeat = millis,
+            Timeout::BlockFetchMin => self.block_fetch_min = millis,
+            Timeout::BlockFetchMax => self.block_fetch_max = millis
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_config(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("grapheners_{}_{}.json", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        return path;
+    }
+
+    #[test]
+    fn layers_file_then_env_then_code() {
+        let path = write_config("layers", r#"{
+            "timeouts": {"ws_send": 111, "ws_receive": 222, "ws_close": 333},
+            "subscription_buffer": 10,
+            "reconnect_max_attempts": 4
+        }"#);
+
+        env::set_var(env_key("ws_receive"), "444");
+        env::set_var(env_key("ws_close"), "555");
+        env::set_var(env_key("reconnect_max_attempts"), "0");
+
+        let config = ClientConfig::builder()
+            .file(&path)
+            .env()
+            .timeout(Timeout::WebsocketClose, 666)
+            .build();
+
+        env::remove_var(env_key("ws_receive"));
+        env::remove_var(env_key("ws_close"));
+        env::remove_var(env_key("reconnect_max_attempts"));
+        fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+
+        assert_eq!(config.get_timeout(Timeout::WebSocketSend), 111);
+        assert_eq!(config.get_timeout(Timeout::WebSocketReceive), 444);
+        assert_eq!(config.get_timeout(Timeout::WebsocketClose), 666);
+        assert_eq!(config.get_timeout(Timeout::ReconnectMax), 30000);
+        assert_eq!(config.get_timeout(Timeout::BlockFetchMin), 250);
+        assert_eq!(config.get_subscription_buffer(), 10);
+        assert_eq!(config.get_reconnect_max_attempts(), 0);
+    }
+
+    #[test]
+    fn code_overrides_file_custom_apis() {
+        let path = write_config("custom_apis", r#"{
+            "custom_apis": [{"id": 1, "name": "from_file"}, {"id": 2, "name": "kept"}]
+        }"#);
+
+        let config = ClientConfig::builder()
+            .file(&path)
+            .custom_api(1, "from_code")
+            .build();
+
+        fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+
+        assert_eq!(config.get_custom_api_name(1), "from_code");
+        assert_eq!(config.get_custom_api_name(2), "kept");
+    }
+
+    #[test]
+    fn rejects_invalid_file() {
+        let path = write_config("invalid", "{ not json");
+
+        let config = ClientConfig::builder().file(&path).build();
+
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(config, Err(ConfigError::Parse(_, _))));
+    }
 }
\ No newline at end of file
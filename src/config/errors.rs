@@ -0,0 +1,21 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum ConfigError {
+    Io(String, String),
+    Parse(String, String),
+    InvalidEnv(String, String)
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, error) => write!(f,"Unable to read config file {}: {}", path, error),
+            ConfigError::Parse(path, error) => write!(f,"Unable to parse config file {}: {}", path, error),
+            ConfigError::InvalidEnv(key, value) => write!(f,"Invalid value for environment variable {}: {:?}", key, value),
+        }
+    }
+}
+
+impl Error for ConfigError {}
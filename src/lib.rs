@@ -0,0 +1,48 @@
+#![allow(clippy::needless_return, clippy::module_inception)]
+
+pub mod websocket;
+pub mod commands;
+pub mod client;
+pub mod config;
+pub mod models;
+
+pub mod prelude {
+    pub use crate::client::client::GrapheneClient;
+    pub use crate::client::apis::{GrapheneApi, GrapheneApis};
+    pub use crate::client::errors::{GrapheneError, RpcError};
+    pub use crate::client::subscription::{SubscriptionHandle, SubscriptionKind, SubscriptionStream};
+    pub use crate::commands::getters::getters::AccountHistoryStream;
+    pub use crate::commands::subscriptions::responses::AccountUpdate;
+    pub use crate::config::config::{ClientConfig, ClientConfigBuilder};
+    pub use crate::config::errors::ConfigError;
+    pub use crate::models::account::{Account, AccountBalance, AccountOptions, AccountStatistics, Authority, FullAccount};
+    pub use crate::models::asset::{
+        Asset, AssetAmount, AssetBitassetData, AssetDetails, AssetDynamicData, AssetHolder, AssetHolderCount, AssetOptions, Price, PriceFeed
+    };
+    pub use crate::models::balance::{BalanceObject, VestingBalance, VestingPolicy};
+    pub use crate::models::block::{BlockHeader, SignedBlock};
+    pub use crate::models::governance::{CommitteeMember, VoteId, VoteType, Witness, Worker};
+    pub use crate::models::history::{BucketKey, MarketBucket, MarketTrade, OperationHistory, OperationHistoryDetail};
+    pub use crate::models::market::{
+        CallOrder, CollateralBid, Decimal, FillOrder, ForceSettlement, LimitOrder, MarketTicker, MarketUpdate, MarketVolume, Order, OrderBook
+    };
+    pub use crate::models::objects::ChainObject;
+    pub use crate::models::operations::{
+        CallOrderUpdateOperation, LimitOrderCancelOperation, LimitOrderCreateOperation, OperationPayload, TransferOperation
+    };
+    pub use crate::models::properties::{
+        ChainConfig, ChainParameters, ChainProperties, DynamicGlobalProperties, FeeParameters, FeeSchedule, GlobalProperties
+    };
+    pub use crate::models::object_id::{
+        ObjectId, ObjectIdError, ObjectType, TypedObjectId,
+        AccountId, AssetId, CallOrderId, CommitteeMemberId, ForceSettlementId, LimitOrderId,
+        BalanceId, OperationHistoryId, ProposalId, VestingBalanceId, WitnessId, WorkerId
+    };
+    pub use crate::models::transaction::{Operation, OperationResult, ProcessedTransaction, SignedTransaction};
+    pub use crate::websocket::service::WebSocket;
+    pub use crate::websocket::errors::WebSocketError;
+    pub use crate::websocket::interface::{IWebSocket, ConnectionState, SessionEvent, SubscriptionCallback};
+    pub use crate::websocket::implementations::fast_websocket::FastWebsocketClient;
+    pub use crate::websocket::implementations::node_pool::{NodePoolClient, NodeHealth};
+    pub use crate::websocket::implementations::timeouts::Timeout;
+}
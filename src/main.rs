@@ -1,14 +1,9 @@
-use crate::websocket::service::WebSocket;
-use crate::websocket::implementations::fast_websocket::FastWebsocketClient;
-use crate::websocket::errors::WebSocketError;
-use crate::client::client::GrapheneClient;
+#![allow(clippy::needless_return)]
 
-use serde_json::Value;
+use grapheners::prelude::*;
+use std::sync::Arc;
 
-mod websocket;
-mod commands;
-mod client;
-mod config;
+use serde_json::Value;
 
 pub async fn watch_account_cb_one(notice: Option<Value>) -> Result<(), WebSocketError> {
     println!("Heya 1");
@@ -25,31 +20,73 @@ pub async fn watch_account_cb_two(notice: Option<Value>) -> Result<(), WebSocket
 #[tokio::main]
 async fn main () {
 
-    let mut concrete_ws = FastWebsocketClient::new(String::from("ws://127.0.0.1:8090"));
+    let config_path = std::env::args().nth(1).unwrap_or(String::from("config.json"));
+
+    let config = Arc::new(ClientConfig::builder()
+        .file(config_path)
+        .env()
+        .build()
+        .expect("Failed to load client config"));
+
+    let mut concrete_ws = FastWebsocketClient::with_config(String::from("ws://127.0.0.1:8090"), Arc::clone(&config));
     
-    let mut graphene_client = GrapheneClient::new();
+    let mut graphene_client = GrapheneClient::with_config(config);
+
+    let (tx_cb_error, mut rx_cb_error) = tokio::sync::mpsc::channel::<(u64, WebSocketError)>(100);
+
+    graphene_client.set_callback_error_channel(tx_cb_error);
+
+    tokio::task::spawn(async move {
+        while let Some((cb_id, error)) = rx_cb_error.recv().await {
+            println!("Callback {} Error: {}", cb_id, error);
+        }
+    });
 
     let tx_cb_call = graphene_client.create_callback_channel();
 
     concrete_ws.set_callback_channel(tx_cb_call);
 
-    let mut ws_service = WebSocket::new(&mut concrete_ws);
+    let tx_session = graphene_client.create_session_channel();
+
+    concrete_ws.set_session_channel(tx_session);
 
-    graphene_client.set_ws_connection(&mut ws_service);
+    let (tx_state, mut rx_state) = tokio::sync::mpsc::channel::<ConnectionState>(100);
+
+    concrete_ws.set_connection_state_channel(tx_state);
+
+    tokio::task::spawn(async move {
+        while let Some(state) = rx_state.recv().await {
+            println!("Connection State: {:?}", state);
+        }
+    });
+
+    let ws_service = WebSocket::new(concrete_ws);
+
+    graphene_client.set_ws_connection(ws_service);
 
     graphene_client.connect().await.expect("Failed to establish websocket connection with server");
 
     graphene_client.init().await.expect("Failed to Initialize Client...");
 
-    let account_to_watch_one = String::from("1.2.7");
+    let client_handle = graphene_client.clone();
+
+    let dynamic_properties: Value = tokio::task::spawn(async move {
+        client_handle.call(GrapheneApi::Database(0), "get_dynamic_global_properties", ()).await
+    }).await.expect("Task panicked").expect("Failed to get dynamic global properties");
+
+    println!("Dynamic Global Properties: {}", dynamic_properties);
+
+    let account_to_watch_one: AccountId = "1.2.7".parse().expect("Invalid account id");
 
-    let account_to_watch_two = String::from("1.3.6");
+    let account_to_watch_two: AccountId = "1.2.6".parse().expect("Invalid account id");
 
-    let _ = graphene_client.subscribe_to_account(Box::new(|x| Box::pin(watch_account_cb_one(x))), vec![account_to_watch_one]).await;
+    let _subscription_one = graphene_client.subscribe_to_account(Box::new(|x| Box::pin(watch_account_cb_one(x))), vec![account_to_watch_one]).await
+        .expect("Failed to subscribe to account");
 
-    let _ = graphene_client.subscribe_to_account(Box::new(|x| Box::pin(watch_account_cb_two(x))), vec![account_to_watch_two]).await;
+    let _subscription_two = graphene_client.subscribe_to_account(Box::new(|x| Box::pin(watch_account_cb_two(x))), vec![account_to_watch_two]).await
+        .expect("Failed to subscribe to account");
 
-    loop{};
+    let _ = tokio::signal::ctrl_c().await;
 
     //graphene_client.close().await.expect("Failed to close websocket connection");
 
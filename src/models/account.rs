@@ -0,0 +1,332 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::asset::deserialize_share_amount;
+use crate::models::balance::VestingBalance;
+use crate::models::governance::VoteId;
+use crate::models::market::{CallOrder, ForceSettlement, LimitOrder};
+use crate::models::objects::ChainObject;
+use crate::models::object_id::{
+    AccountBalanceId, AccountId, AccountStatisticsId, AssetId, ObjectId, VestingBalanceId
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Authority {
+    pub weight_threshold: u32,
+    #[serde(default)]
+    pub account_auths: Vec<(AccountId, u16)>,
+    #[serde(default)]
+    pub key_auths: Vec<(String, u16)>,
+    #[serde(default)]
+    pub address_auths: Vec<(String, u16)>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountOptions {
+    pub memo_key: String,
+    pub voting_account: AccountId,
+    pub num_witness: u16,
+    pub num_committee: u16,
+    #[serde(default)]
+    pub votes: Vec<VoteId>,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub id: AccountId,
+    pub membership_expiration_date: NaiveDateTime,
+    pub registrar: AccountId,
+    pub referrer: AccountId,
+    pub lifetime_referrer: AccountId,
+    pub network_fee_percentage: u16,
+    pub lifetime_referrer_fee_percentage: u16,
+    pub referrer_rewards_percentage: u16,
+    pub name: String,
+    pub owner: Authority,
+    pub active: Authority,
+    pub options: AccountOptions,
+    pub statistics: AccountStatisticsId,
+    #[serde(default)]
+    pub whitelisting_accounts: Vec<AccountId>,
+    #[serde(default)]
+    pub blacklisting_accounts: Vec<AccountId>,
+    #[serde(default)]
+    pub whitelisted_accounts: Vec<AccountId>,
+    #[serde(default)]
+    pub blacklisted_accounts: Vec<AccountId>,
+    #[serde(default)]
+    pub cashback_vb: Option<VestingBalanceId>,
+    #[serde(default)]
+    pub owner_special_authority: Value,
+    #[serde(default)]
+    pub active_special_authority: Value,
+    #[serde(default)]
+    pub top_n_control_flags: u8,
+    #[serde(default)]
+    pub allowed_assets: Option<Vec<AssetId>>,
+    #[serde(default)]
+    pub creation_block_num: u32,
+    #[serde(default)]
+    pub creation_time: Option<NaiveDateTime>
+}
+
+impl Account {
+    pub fn is_lifetime_member(&self) -> bool {
+        return self.membership_expiration_date.and_utc().timestamp() == u32::MAX as i64;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBalance {
+    pub id: AccountBalanceId,
+    pub owner: AccountId,
+    pub asset_type: AssetId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub balance: i64,
+    #[serde(default)]
+    pub maintenance_flag: bool
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountStatistics {
+    pub id: AccountStatisticsId,
+    pub owner: AccountId,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub most_recent_op: ObjectId,
+    #[serde(default)]
+    pub total_ops: u64,
+    #[serde(default)]
+    pub removed_ops: u64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub total_core_in_orders: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub core_in_balance: i64,
+    #[serde(default)]
+    pub has_cashback_vb: bool,
+    #[serde(default)]
+    pub is_voting: bool,
+    #[serde(default)]
+    pub last_vote_time: Option<NaiveDateTime>,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub lifetime_fees_paid: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub pending_fees: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub pending_vested_fees: i64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FullAccount {
+    pub account: Account,
+    pub statistics: AccountStatistics,
+    pub registrar_name: String,
+    pub referrer_name: String,
+    pub lifetime_referrer_name: String,
+    #[serde(default)]
+    pub votes: Vec<ChainObject>,
+    #[serde(default)]
+    pub cashback_balance: Option<VestingBalance>,
+    #[serde(default)]
+    pub balances: Vec<AccountBalance>,
+    #[serde(default)]
+    pub vesting_balances: Vec<VestingBalance>,
+    #[serde(default)]
+    pub limit_orders: Vec<LimitOrder>,
+    #[serde(default)]
+    pub call_orders: Vec<CallOrder>,
+    #[serde(default)]
+    pub settle_orders: Vec<ForceSettlement>,
+    #[serde(default)]
+    pub proposals: Vec<Value>,
+    #[serde(default)]
+    pub assets: Vec<AssetId>,
+    #[serde(default)]
+    pub withdraws_from: Vec<Value>,
+    #[serde(default)]
+    pub withdraws_to: Vec<Value>,
+    #[serde(default)]
+    pub htlcs_from: Vec<Value>,
+    #[serde(default)]
+    pub htlcs_to: Vec<Value>,
+    #[serde(default)]
+    pub more_data_available: Value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::models::balance::VestingPolicy;
+    use crate::models::governance::VoteType;
+
+    fn authority() -> Value {
+        return json!({
+            "weight_threshold": 1,
+            "account_auths": [["1.2.5", 1]],
+            "key_auths": [["BTS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV", 1]],
+            "address_auths": []
+        });
+    }
+
+    fn full_account_response() -> Value {
+        return json!([["init0", {
+            "account": {
+                "id": "1.2.100",
+                "membership_expiration_date": "2106-02-07T06:28:15",
+                "registrar": "1.2.0",
+                "referrer": "1.2.0",
+                "lifetime_referrer": "1.2.100",
+                "network_fee_percentage": 2000,
+                "lifetime_referrer_fee_percentage": 8000,
+                "referrer_rewards_percentage": 0,
+                "name": "init0",
+                "owner": authority(),
+                "active": authority(),
+                "options": {
+                    "memo_key": "BTS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV",
+                    "voting_account": "1.2.5",
+                    "num_witness": 0,
+                    "num_committee": 0,
+                    "votes": ["1:22", "0:11", "2:205"],
+                    "extensions": []
+                },
+                "num_committee_voted": 0,
+                "statistics": "2.6.100",
+                "whitelisting_accounts": [],
+                "blacklisting_accounts": [],
+                "whitelisted_accounts": [],
+                "blacklisted_accounts": [],
+                "cashback_vb": "1.13.0",
+                "owner_special_authority": [0, {}],
+                "active_special_authority": [0, {}],
+                "top_n_control_flags": 0,
+                "creation_block_num": 0,
+                "creation_time": "1970-01-01T00:00:00"
+            },
+            "statistics": {
+                "id": "2.6.100",
+                "owner": "1.2.100",
+                "name": "init0",
+                "most_recent_op": "2.9.1234",
+                "total_ops": 42,
+                "removed_ops": 0,
+                "total_core_in_orders": "100000",
+                "total_core_inactive": 0,
+                "core_in_balance": "500000",
+                "has_cashback_vb": true,
+                "is_voting": true,
+                "last_vote_time": "2021-03-01T00:00:00",
+                "lifetime_fees_paid": "123456",
+                "pending_fees": 0,
+                "pending_vested_fees": 0
+            },
+            "registrar_name": "committee-account",
+            "referrer_name": "committee-account",
+            "lifetime_referrer_name": "init0",
+            "votes": [{
+                "id": "1.6.1",
+                "witness_account": "1.2.6",
+                "last_aslot": 62070000,
+                "signing_key": "BTS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV",
+                "pay_vb": "1.13.1",
+                "vote_id": "1:0",
+                "total_votes": "123456789012",
+                "url": "",
+                "total_missed": 1000,
+                "last_confirmed_block_num": 62121954
+            }],
+            "cashback_balance": {
+                "id": "1.13.0",
+                "owner": "1.2.100",
+                "balance": {"amount": "1000", "asset_id": "1.3.0"},
+                "policy": [1, {
+                    "vesting_seconds": 7776000,
+                    "coin_seconds_earned": "0",
+                    "coin_seconds_earned_last_update": "2020-01-01T00:00:00",
+                    "start_claim": "1970-01-01T00:00:00"
+                }],
+                "balance_type": "cashback"
+            },
+            "balances": [{
+                "id": "2.5.1",
+                "owner": "1.2.100",
+                "asset_type": "1.3.0",
+                "balance": "1234567890123",
+                "maintenance_flag": false
+            }],
+            "vesting_balances": [],
+            "limit_orders": [{
+                "id": "1.7.5",
+                "expiration": "2030-01-01T00:00:00",
+                "seller": "1.2.100",
+                "for_sale": 500,
+                "sell_price": {
+                    "base": {"amount": 500, "asset_id": "1.3.0"},
+                    "quote": {"amount": 1000, "asset_id": "1.3.113"}
+                },
+                "deferred_fee": 0,
+                "deferred_paid_fee": {"amount": 0, "asset_id": "1.3.0"}
+            }],
+            "call_orders": [{
+                "id": "1.8.1",
+                "borrower": "1.2.100",
+                "collateral": "1000000",
+                "debt": 1000,
+                "call_price": {
+                    "base": {"amount": 1, "asset_id": "1.3.0"},
+                    "quote": {"amount": 1, "asset_id": "1.3.113"}
+                },
+                "target_collateral_ratio": 1750
+            }],
+            "settle_orders": [],
+            "proposals": [],
+            "assets": ["1.3.113"],
+            "withdraws_from": [],
+            "withdraws_to": [],
+            "htlcs_from": [],
+            "htlcs_to": [],
+            "more_data_available": {"balances": false, "vesting_balances": false}
+        }]]);
+    }
+
+    #[test]
+    fn full_account_deserializes_from_node_response() {
+        let response: Vec<(String, FullAccount)> = serde_json::from_value(full_account_response()).unwrap();
+        let (name, full_account) = &response[0];
+
+        assert_eq!(name, "init0");
+        assert_eq!(full_account.account.id, AccountId::new(100));
+        assert!(full_account.account.is_lifetime_member());
+        assert_eq!(full_account.account.owner.account_auths, vec![(AccountId::new(5), 1)]);
+        assert_eq!(full_account.account.options.votes[2], VoteId::new(VoteType::Worker, 205));
+        assert_eq!(full_account.account.cashback_vb, Some(VestingBalanceId::new(0)));
+
+        assert_eq!(full_account.statistics.most_recent_op, "2.9.1234".parse().unwrap());
+        assert_eq!(full_account.statistics.total_core_in_orders, 100000);
+        assert_eq!(full_account.statistics.lifetime_fees_paid, 123456);
+
+        assert!(matches!(&full_account.votes[0], ChainObject::Witness(witness) if witness.total_votes == 123456789012));
+        assert!(matches!(full_account.cashback_balance.as_ref().map(|balance| &balance.policy), Some(VestingPolicy::Cdd(_))));
+        assert_eq!(full_account.balances[0].balance, 1234567890123);
+        assert_eq!(full_account.limit_orders[0].seller, AccountId::new(100));
+        assert_eq!(full_account.call_orders[0].collateral, 1000000);
+        assert_eq!(full_account.call_orders[0].target_collateral_ratio, Some(1750));
+        assert_eq!(full_account.assets, vec![AssetId::new(113)]);
+    }
+
+    #[test]
+    fn account_without_lifetime_membership() {
+        let mut response = full_account_response();
+        response[0][1]["account"]["membership_expiration_date"] = json!("1970-01-01T00:00:00");
+        response[0][1]["account"]["cashback_vb"] = Value::Null;
+
+        let account: Account = serde_json::from_value(response[0][1]["account"].clone()).unwrap();
+
+        assert!(!account.is_lifetime_member());
+        assert_eq!(account.cashback_vb, None);
+    }
+}
\ No newline at end of file
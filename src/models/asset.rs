@@ -0,0 +1,373 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::models::object_id::{AccountId, AssetBitassetDataId, AssetDynamicDataId, AssetId};
+
+pub fn deserialize_share_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+
+    let amount = match &value {
+        Value::Number(number) => number.as_i64(),
+        Value::String(text) => text.parse::<i64>().ok(),
+        _ => None
+    };
+
+    return amount.ok_or_else(|| serde::de::Error::custom(format!("invalid share amount: {}", value)));
+}
+
+pub fn deserialize_optional_share_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    let value = Option::<Value>::deserialize(deserializer)?;
+
+    if let Some(value) = value {
+        return deserialize_share_amount(value).map(Some).map_err(serde::de::Error::custom);
+    } else {
+        return Ok(None);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetAmount {
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub amount: i64,
+    pub asset_id: AssetId
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Price {
+    pub base: AssetAmount,
+    pub quote: AssetAmount
+}
+
+impl Price {
+    pub fn to_f64(&self, base_precision: u8, quote_precision: u8) -> Option<f64> {
+        if self.quote.amount == 0 {
+            return None;
+        }
+
+        let base = self.base.amount as f64 / 10f64.powi(base_precision as i32);
+        let quote = self.quote.amount as f64 / 10f64.powi(quote_precision as i32);
+
+        return Some(base / quote);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetOptions {
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub max_supply: i64,
+    pub market_fee_percent: u16,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub max_market_fee: i64,
+    pub issuer_permissions: u16,
+    pub flags: u16,
+    pub core_exchange_rate: Price,
+    #[serde(default)]
+    pub whitelist_authorities: Vec<AccountId>,
+    #[serde(default)]
+    pub blacklist_authorities: Vec<AccountId>,
+    #[serde(default)]
+    pub whitelist_markets: Vec<AssetId>,
+    #[serde(default)]
+    pub blacklist_markets: Vec<AssetId>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asset {
+    pub id: AssetId,
+    pub symbol: String,
+    pub precision: u8,
+    pub issuer: AccountId,
+    pub options: AssetOptions,
+    pub dynamic_asset_data_id: AssetDynamicDataId,
+    #[serde(default)]
+    pub bitasset_data_id: Option<AssetBitassetDataId>,
+    #[serde(default)]
+    pub buyback_account: Option<AccountId>,
+    #[serde(default)]
+    pub creation_block_num: u32,
+    #[serde(default)]
+    pub creation_time: Option<NaiveDateTime>,
+    #[serde(default, deserialize_with = "deserialize_optional_share_amount")]
+    pub total_in_collateral: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_share_amount")]
+    pub total_backing_collateral: Option<i64>
+}
+
+impl Asset {
+    pub fn is_market_issued(&self) -> bool {
+        return self.bitasset_data_id.is_some();
+    }
+
+    pub fn amount_to_f64(&self, amount: i64) -> f64 {
+        return amount as f64 / 10f64.powi(self.precision as i32);
+    }
+
+    pub fn format_amount(&self, amount: i64) -> String {
+        if self.precision == 0 {
+            return format!("{} {}", amount, self.symbol);
+        }
+
+        let scale = 10u64.pow(self.precision as u32);
+        let sign = if amount < 0 { "-" } else { "" };
+        let whole = amount.unsigned_abs() / scale;
+        let fraction = amount.unsigned_abs() % scale;
+
+        return format!("{}{}.{:0width$} {}", sign, whole, fraction, self.symbol, width = self.precision as usize);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetHolder {
+    pub name: String,
+    pub account_id: AccountId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub amount: i64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetHolderCount {
+    pub asset_id: AssetId,
+    pub count: u64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetDynamicData {
+    pub id: AssetDynamicDataId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub current_supply: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub confidential_supply: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub accumulated_fees: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub accumulated_collateral_fees: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub fee_pool: i64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceFeed {
+    pub settlement_price: Price,
+    pub maintenance_collateral_ratio: u16,
+    pub maximum_short_squeeze_ratio: u16,
+    pub core_exchange_rate: Price
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitassetOptions {
+    pub feed_lifetime_sec: u32,
+    pub minimum_feeds: u8,
+    pub force_settlement_delay_sec: u32,
+    pub force_settlement_offset_percent: u16,
+    pub maximum_force_settlement_volume: u16,
+    pub short_backing_asset: AssetId,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetBitassetData {
+    pub id: AssetBitassetDataId,
+    pub asset_id: AssetId,
+    pub options: BitassetOptions,
+    #[serde(default)]
+    pub feeds: Vec<(AccountId, (NaiveDateTime, PriceFeed))>,
+    pub current_feed: PriceFeed,
+    pub current_feed_publication_time: NaiveDateTime,
+    #[serde(default)]
+    pub is_prediction_market: bool,
+    pub settlement_price: Price,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub settlement_fund: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub force_settled_volume: i64
+}
+
+#[derive(Debug, Clone)]
+pub struct AssetDetails {
+    pub asset: Asset,
+    pub dynamic_data: AssetDynamicData,
+    pub bitasset_data: Option<AssetBitassetData>
+}
+
+impl AssetBitassetData {
+    pub fn has_settlement(&self) -> bool {
+        return self.settlement_price.base.amount != 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn price(base: i64, base_asset: &str, quote: i64, quote_asset: &str) -> Value {
+        return json!({
+            "base": {"amount": base, "asset_id": base_asset},
+            "quote": {"amount": quote, "asset_id": quote_asset}
+        });
+    }
+
+    fn price_feed(settlement_base: i64) -> Value {
+        return json!({
+            "settlement_price": price(settlement_base, "1.3.113", 10000000, "1.3.0"),
+            "maintenance_collateral_ratio": 1750,
+            "maximum_short_squeeze_ratio": 1100,
+            "core_exchange_rate": price(2500, "1.3.113", 10500000, "1.3.0")
+        });
+    }
+
+    #[test]
+    fn market_issued_asset() {
+        let asset: Asset = serde_json::from_value(json!({
+            "id": "1.3.113",
+            "symbol": "CNY",
+            "precision": 4,
+            "issuer": "1.2.0",
+            "options": {
+                "max_supply": "1000000000000000",
+                "market_fee_percent": 0,
+                "max_market_fee": "1000000000000000",
+                "issuer_permissions": 511,
+                "flags": 128,
+                "core_exchange_rate": price(2500, "1.3.113", 10500000, "1.3.0"),
+                "whitelist_authorities": [],
+                "blacklist_authorities": [],
+                "whitelist_markets": [],
+                "blacklist_markets": [],
+                "description": "1 Chinese yuan",
+                "extensions": {}
+            },
+            "dynamic_asset_data_id": "2.3.113",
+            "bitasset_data_id": "2.4.13",
+            "creation_block_num": 0,
+            "creation_time": "2015-10-13T14:12:24",
+            "total_in_collateral": "90183734212543",
+            "total_backing_collateral": 90183734212543i64
+        })).unwrap();
+
+        assert!(asset.is_market_issued());
+        assert_eq!(asset.options.max_supply, 1000000000000000);
+        assert_eq!(asset.total_in_collateral, Some(90183734212543));
+        assert_eq!(asset.total_backing_collateral, Some(90183734212543));
+        assert_eq!(asset.format_amount(-123456), "-12.3456 CNY");
+        assert_eq!(asset.options.core_exchange_rate.to_f64(asset.precision, 5), Some(2500.0 / 10000.0 / 105.0));
+    }
+
+    #[test]
+    fn user_issued_asset_without_optional_fields() {
+        let asset: Asset = serde_json::from_value(json!({
+            "id": "1.3.1000",
+            "symbol": "TOKEN",
+            "precision": 0,
+            "issuer": "1.2.100",
+            "options": {
+                "max_supply": 1000,
+                "market_fee_percent": 10,
+                "max_market_fee": 0,
+                "issuer_permissions": 79,
+                "flags": 0,
+                "core_exchange_rate": price(1, "1.3.0", 1, "1.3.1000")
+            },
+            "dynamic_asset_data_id": "2.3.1000",
+            "total_in_collateral": null
+        })).unwrap();
+
+        assert!(!asset.is_market_issued());
+        assert_eq!(asset.total_in_collateral, None);
+        assert_eq!(asset.total_backing_collateral, None);
+        assert_eq!(asset.format_amount(42), "42 TOKEN");
+    }
+
+    #[test]
+    fn dynamic_data_accepts_string_and_number_amounts() {
+        let data: AssetDynamicData = serde_json::from_value(json!({
+            "id": "2.3.0",
+            "current_supply": "2999999999999999",
+            "confidential_supply": "1000",
+            "accumulated_fees": 0,
+            "fee_pool": 31415
+        })).unwrap();
+
+        assert_eq!(data.current_supply, 2999999999999999);
+        assert_eq!(data.confidential_supply, 1000);
+        assert_eq!(data.accumulated_collateral_fees, 0);
+        assert_eq!(data.fee_pool, 31415);
+    }
+
+    #[test]
+    fn invalid_share_amounts_are_rejected() {
+        for amount in [json!("12.5"), json!("abc"), json!(1.5), json!(true), json!(null), json!("18446744073709551615")] {
+            let result: Result<AssetAmount, _> = serde_json::from_value(json!({"amount": amount, "asset_id": "1.3.0"}));
+
+            assert!(result.is_err(), "accepted {}", amount);
+        }
+    }
+
+    #[test]
+    fn bitasset_data_with_feeds() {
+        let data: AssetBitassetData = serde_json::from_value(json!({
+            "id": "2.4.13",
+            "asset_id": "1.3.113",
+            "options": {
+                "feed_lifetime_sec": 86400,
+                "minimum_feeds": 7,
+                "force_settlement_delay_sec": 86400,
+                "force_settlement_offset_percent": 100,
+                "maximum_force_settlement_volume": 2000,
+                "short_backing_asset": "1.3.0",
+                "extensions": {}
+            },
+            "feeds": [
+                ["1.2.6", ["2021-11-20T10:10:00", price_feed(2500)]],
+                ["1.2.7", ["2021-11-20T10:12:30", price_feed(2510)]]
+            ],
+            "current_feed": price_feed(2505),
+            "current_feed_publication_time": "2021-11-20T10:12:30",
+            "is_prediction_market": false,
+            "settlement_price": price(0, "1.3.113", 0, "1.3.0"),
+            "settlement_fund": "0",
+            "force_settled_volume": 0
+        })).unwrap();
+
+        let (publisher, (published, feed)) = &data.feeds[1];
+
+        assert_eq!(data.feeds.len(), 2);
+        assert_eq!(*publisher, AccountId::new(7));
+        assert_eq!(published.to_string(), "2021-11-20 10:12:30");
+        assert_eq!(feed.settlement_price.base.amount, 2510);
+        assert_eq!(feed.maintenance_collateral_ratio, 1750);
+        assert_eq!(data.current_feed.settlement_price.base.amount, 2505);
+        assert!(!data.has_settlement());
+    }
+
+    #[test]
+    fn globally_settled_bitasset_without_feeds() {
+        let data: AssetBitassetData = serde_json::from_value(json!({
+            "id": "2.4.20",
+            "asset_id": "1.3.120",
+            "options": {
+                "feed_lifetime_sec": 86400,
+                "minimum_feeds": 1,
+                "force_settlement_delay_sec": 86400,
+                "force_settlement_offset_percent": 0,
+                "maximum_force_settlement_volume": 2000,
+                "short_backing_asset": "1.3.0"
+            },
+            "current_feed": price_feed(0),
+            "current_feed_publication_time": "2019-01-01T00:00:00",
+            "settlement_price": price(10000, "1.3.120", 4000000, "1.3.0"),
+            "settlement_fund": "4000000"
+        })).unwrap();
+
+        assert!(data.feeds.is_empty());
+        assert!(data.has_settlement());
+        assert_eq!(data.settlement_fund, 4000000);
+        assert_eq!(data.force_settled_volume, 0);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,181 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+
+use crate::models::asset::{AssetAmount, deserialize_share_amount};
+use crate::models::object_id::{AccountId, BalanceId, VestingBalanceId};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinearVestingPolicy {
+    pub begin_timestamp: NaiveDateTime,
+    pub vesting_cliff_seconds: u32,
+    pub vesting_duration_seconds: u32,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub begin_balance: i64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CddVestingPolicy {
+    pub vesting_seconds: u32,
+    #[serde(default)]
+    pub coin_seconds_earned: String,
+    pub coin_seconds_earned_last_update: NaiveDateTime,
+    pub start_claim: NaiveDateTime
+}
+
+#[derive(Debug, Clone)]
+pub enum VestingPolicy {
+    Linear(LinearVestingPolicy),
+    Cdd(CddVestingPolicy),
+    Instant,
+    Unknown(u8, Value)
+}
+
+impl<'de> Deserialize<'de> for VestingPolicy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (policy_type, policy): (u8, Value) = Deserialize::deserialize(deserializer)?;
+
+        match policy_type {
+            0 => return serde_json::from_value(policy).map(VestingPolicy::Linear).map_err(serde::de::Error::custom),
+            1 => return serde_json::from_value(policy).map(VestingPolicy::Cdd).map_err(serde::de::Error::custom),
+            2 => return Ok(VestingPolicy::Instant),
+            _ => return Ok(VestingPolicy::Unknown(policy_type, policy))
+        }
+    }
+}
+
+impl Serialize for VestingPolicy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            VestingPolicy::Linear(policy) => (0u8, policy).serialize(serializer),
+            VestingPolicy::Cdd(policy) => (1u8, policy).serialize(serializer),
+            VestingPolicy::Instant => (2u8, json!({})).serialize(serializer),
+            VestingPolicy::Unknown(policy_type, policy) => (policy_type, policy).serialize(serializer)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VestingBalance {
+    pub id: VestingBalanceId,
+    pub owner: AccountId,
+    pub balance: AssetAmount,
+    pub policy: VestingPolicy,
+    #[serde(default)]
+    pub balance_type: Option<String>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceObject {
+    pub id: BalanceId,
+    pub owner: String,
+    pub balance: AssetAmount,
+    #[serde(default)]
+    pub vesting_policy: Option<LinearVestingPolicy>,
+    #[serde(default)]
+    pub last_claim_date: Option<NaiveDateTime>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vesting_balance(policy: Value) -> Value {
+        return json!({
+            "id": "1.13.24",
+            "owner": "1.2.100",
+            "balance": {"amount": "7500000", "asset_id": "1.3.0"},
+            "policy": policy,
+            "balance_type": "worker"
+        });
+    }
+
+    #[test]
+    fn linear_vesting_policy() {
+        let balance: VestingBalance = serde_json::from_value(vesting_balance(json!([0, {
+            "begin_timestamp": "2015-10-13T14:12:24",
+            "vesting_cliff_seconds": 0,
+            "vesting_duration_seconds": 31536000,
+            "begin_balance": "100000000000"
+        }]))).unwrap();
+
+        assert_eq!(balance.id, VestingBalanceId::new(24));
+        assert_eq!(balance.balance.amount, 7500000);
+        assert_eq!(balance.balance_type.as_deref(), Some("worker"));
+        if let VestingPolicy::Linear(policy) = &balance.policy {
+            assert_eq!(policy.vesting_duration_seconds, 31536000);
+            assert_eq!(policy.begin_balance, 100000000000);
+        } else {
+            panic!("expected linear policy, got {:?}", balance.policy);
+        }
+    }
+
+    #[test]
+    fn cdd_vesting_policy() {
+        let balance: VestingBalance = serde_json::from_value(vesting_balance(json!([1, {
+            "vesting_seconds": 86400,
+            "coin_seconds_earned": "648000000000000",
+            "coin_seconds_earned_last_update": "2021-05-01T12:00:00",
+            "start_claim": "1970-01-01T00:00:00"
+        }]))).unwrap();
+
+        if let VestingPolicy::Cdd(policy) = &balance.policy {
+            assert_eq!(policy.vesting_seconds, 86400);
+            assert_eq!(policy.coin_seconds_earned, "648000000000000");
+        } else {
+            panic!("expected cdd policy, got {:?}", balance.policy);
+        }
+    }
+
+    #[test]
+    fn instant_and_unknown_vesting_policies() {
+        let instant: VestingPolicy = serde_json::from_value(json!([2, {}])).unwrap();
+        let unknown: VestingPolicy = serde_json::from_value(json!([7, {"future": true}])).unwrap();
+
+        assert!(matches!(instant, VestingPolicy::Instant));
+        assert!(matches!(&unknown, VestingPolicy::Unknown(7, policy) if policy["future"] == json!(true)));
+        assert_eq!(serde_json::to_value(&instant).unwrap(), json!([2, {}]));
+        assert_eq!(serde_json::to_value(&unknown).unwrap(), json!([7, {"future": true}]));
+    }
+
+    #[test]
+    fn malformed_known_policy_is_an_error() {
+        let result: Result<VestingPolicy, _> = serde_json::from_value(json!([0, {"vesting_seconds": 86400}]));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn vesting_policy_round_trips() {
+        let policy = json!([1, {
+            "vesting_seconds": 86400,
+            "coin_seconds_earned": "0",
+            "coin_seconds_earned_last_update": "2021-05-01T12:00:00",
+            "start_claim": "1970-01-01T00:00:00"
+        }]);
+
+        let parsed: VestingPolicy = serde_json::from_value(policy.clone()).unwrap();
+
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), policy);
+    }
+
+    #[test]
+    fn genesis_balance_object() {
+        let balance: BalanceObject = serde_json::from_value(json!({
+            "id": "1.15.0",
+            "owner": "BTSAnRPeBx6fQrsGz2nhe6hdwmv4RN1k1TmN",
+            "balance": {"amount": 3000000, "asset_id": "1.3.0"},
+            "vesting_policy": {
+                "begin_timestamp": "2015-10-13T14:12:24",
+                "vesting_cliff_seconds": 0,
+                "vesting_duration_seconds": 63072000,
+                "begin_balance": 3000000
+            },
+            "last_claim_date": "1970-01-01T00:00:00"
+        })).unwrap();
+
+        assert_eq!(balance.id, BalanceId::new(0));
+        assert_eq!(balance.owner, "BTSAnRPeBx6fQrsGz2nhe6hdwmv4RN1k1TmN");
+        assert_eq!(balance.vesting_policy.map(|policy| policy.vesting_duration_seconds), Some(63072000));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,139 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::object_id::WitnessId;
+use crate::models::transaction::ProcessedTransaction;
+
+pub fn block_num_from_id(block_id: &str) -> Option<u32> {
+    let prefix = block_id.get(0..8)?;
+    return u32::from_str_radix(prefix, 16).ok();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub previous: String,
+    pub timestamp: NaiveDateTime,
+    pub witness: WitnessId,
+    pub transaction_merkle_root: String,
+    #[serde(default)]
+    pub extensions: Vec<Value>
+}
+
+impl BlockHeader {
+    pub fn block_num(&self) -> u32 {
+        return block_num_from_id(&self.previous).map(|previous| previous + 1).unwrap_or(1);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedBlock {
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    pub witness_signature: String,
+    #[serde(default)]
+    pub transactions: Vec<ProcessedTransaction>,
+    #[serde(default)]
+    pub block_id: Option<String>,
+    #[serde(default)]
+    pub signing_key: Option<String>
+}
+
+impl SignedBlock {
+    pub fn block_num(&self) -> u32 {
+        return self.header.block_num();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::models::object_id::ObjectId;
+
+    fn signed_block() -> Value {
+        return json!({
+            "previous": "03b3e7e1c6a4e3c80b53bb7b0de1e4c2fd1ba53b",
+            "timestamp": "2021-11-20T10:15:42",
+            "witness": "1.6.69",
+            "transaction_merkle_root": "5d4e0cf5c2b8a4bbd5dc14d1e2e0b7ad0e4a34f1",
+            "extensions": [],
+            "witness_signature": "1f3c6d0d4a4b9e0f07c5b4f3c1e7f4a7bb7e6f1c3a1d2f1e0c9b8a7f6e5d4c3b2a1f0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b2a1f0",
+            "transactions": [{
+                "ref_block_num": 59361,
+                "ref_block_prefix": 3370361030u32,
+                "expiration": "2021-11-20T10:16:09",
+                "operations": [
+                    [1, {
+                        "fee": {"amount": 482, "asset_id": "1.3.0"},
+                        "seller": "1.2.1234",
+                        "amount_to_sell": {"amount": 100000, "asset_id": "1.3.0"},
+                        "min_to_receive": {"amount": 2500, "asset_id": "1.3.113"},
+                        "expiration": "2021-11-27T10:15:39",
+                        "fill_or_kill": false,
+                        "extensions": []
+                    }],
+                    [2, {
+                        "fee": {"amount": 48, "asset_id": "1.3.0"},
+                        "fee_paying_account": "1.2.1234",
+                        "order": "1.7.99",
+                        "extensions": []
+                    }]
+                ],
+                "extensions": [],
+                "signatures": ["1f54c5e1f9d4d3ce0ab0fd9d2e5d7e3c1b6f8e9a0d4c3b2a1f0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b2a1f0e9d8c7b6a5f4e3d2c1"],
+                "operation_results": [[1, "1.7.123"], [2, {"amount": 100000, "asset_id": "1.3.0"}]]
+            }],
+            "block_id": "03b3e7e21df13b1a7a0fd9a1ff2dcb14b1ab26ce",
+            "signing_key": "BTS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV"
+        });
+    }
+
+    #[test]
+    fn signed_block_flattens_header_and_transactions() {
+        let block: SignedBlock = serde_json::from_value(signed_block()).unwrap();
+        let transaction = &block.transactions[0];
+
+        assert_eq!(block.block_num(), 62121954);
+        assert_eq!(block.block_id.as_deref().and_then(block_num_from_id), Some(block.block_num()));
+        assert_eq!(block.header.witness, WitnessId::new(69));
+        assert_eq!(transaction.transaction.ref_block_num, 59361);
+        assert_eq!(transaction.transaction.operations.len(), 2);
+        assert_eq!(transaction.transaction.operations[1].get_type(), 2);
+        assert_eq!(transaction.operation_results[0].get_object_id(), Some("1.7.123".parse::<ObjectId>().unwrap()));
+        assert_eq!(transaction.operation_results[1].get_object_id(), None);
+        assert_eq!(transaction.operation_results[1].get_payload()["amount"], json!(100000));
+    }
+
+    #[test]
+    fn genesis_successor_and_minimal_block() {
+        let mut value = signed_block();
+        let object = value.as_object_mut().unwrap();
+        object.insert("previous".to_string(), json!("0000000000000000000000000000000000000000"));
+        object.remove("transactions");
+        object.remove("block_id");
+        object.remove("signing_key");
+        object.remove("extensions");
+
+        let block: SignedBlock = serde_json::from_value(value).unwrap();
+
+        assert_eq!(block.block_num(), 1);
+        assert!(block.transactions.is_empty());
+        assert!(block.block_id.is_none());
+    }
+
+    #[test]
+    fn block_num_is_read_from_id_prefix() {
+        assert_eq!(block_num_from_id("0000000a5e0b9fc3b1c0c1a4e7d2f1c0a0b0c0d0"), Some(10));
+        assert_eq!(block_num_from_id("04c4b3e1aa6f8d52b0a7e4f1c2d3e4f5a6b7c8d9"), Some(0x04c4b3e1));
+        assert_eq!(block_num_from_id("ffffffff"), Some(u32::MAX));
+    }
+
+    #[test]
+    fn block_num_rejects_short_or_invalid_ids() {
+        assert_eq!(block_num_from_id(""), None);
+        assert_eq!(block_num_from_id("0000000"), None);
+        assert_eq!(block_num_from_id("zz00000a5e0b"), None);
+        assert_eq!(block_num_from_id("é0000000a"), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,249 @@
+use std::fmt;
+use std::str::FromStr;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::models::asset::deserialize_share_amount;
+use crate::models::object_id::{AccountId, CommitteeMemberId, VestingBalanceId, WitnessId, WorkerId};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VoteType {
+    Committee,
+    Witness,
+    Worker,
+    Other(u8)
+}
+
+impl From<u8> for VoteType {
+    fn from(vote_type: u8) -> VoteType {
+        match vote_type {
+            0 => VoteType::Committee,
+            1 => VoteType::Witness,
+            2 => VoteType::Worker,
+            _ => VoteType::Other(vote_type)
+        }
+    }
+}
+
+impl From<VoteType> for u8 {
+    fn from(vote_type: VoteType) -> u8 {
+        match vote_type {
+            VoteType::Committee => 0,
+            VoteType::Witness => 1,
+            VoteType::Worker => 2,
+            VoteType::Other(vote_type) => vote_type
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VoteId {
+    pub vote_type: VoteType,
+    pub instance: u32
+}
+
+impl VoteId {
+    pub fn new(vote_type: VoteType, instance: u32) -> Self {
+        Self { vote_type, instance }
+    }
+}
+
+impl fmt::Display for VoteId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", u8::from(self.vote_type), self.instance)
+    }
+}
+
+impl FromStr for VoteId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((vote_type, instance)) = s.split_once(':') {
+            let vote_type = vote_type.parse::<u8>().map_err(|_e| format!("invalid vote id: {}", s))?;
+            let instance = instance.parse::<u32>().map_err(|_e| format!("invalid vote id: {}", s))?;
+            return Ok(VoteId::new(vote_type.into(), instance));
+        } else {
+            return Err(format!("invalid vote id: {}", s));
+        }
+    }
+}
+
+impl Serialize for VoteId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for VoteId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        return id.parse().map_err(serde::de::Error::custom);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Witness {
+    pub id: WitnessId,
+    pub witness_account: AccountId,
+    #[serde(default)]
+    pub last_aslot: u64,
+    pub signing_key: String,
+    #[serde(default)]
+    pub pay_vb: Option<VestingBalanceId>,
+    pub vote_id: VoteId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub total_votes: i64,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub total_missed: i64,
+    #[serde(default)]
+    pub last_confirmed_block_num: u32
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteeMember {
+    pub id: CommitteeMemberId,
+    pub committee_member_account: AccountId,
+    pub vote_id: VoteId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub total_votes: i64,
+    #[serde(default)]
+    pub url: String
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Worker {
+    pub id: WorkerId,
+    pub worker_account: AccountId,
+    pub work_begin_date: NaiveDateTime,
+    pub work_end_date: NaiveDateTime,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub daily_pay: i64,
+    pub worker: (u8, Value),
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    pub vote_for: VoteId,
+    pub vote_against: VoteId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub total_votes_for: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub total_votes_against: i64
+}
+
+impl Worker {
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        return self.work_begin_date <= now && now < self.work_end_date;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn worker() -> Value {
+        return json!({
+            "id": "1.14.205",
+            "worker_account": "1.2.1000",
+            "work_begin_date": "2021-06-01T00:00:00",
+            "work_end_date": "2022-06-01T00:00:00",
+            "daily_pay": "5000000000",
+            "worker": [1, {"balance": "1.13.1500"}],
+            "name": "Core development",
+            "url": "https://example.org/worker",
+            "vote_for": "2:410",
+            "vote_against": "2:411",
+            "total_votes_for": "210000000000000",
+            "total_votes_against": 0
+        });
+    }
+
+    #[test]
+    fn vote_id_parses_and_displays() {
+        let cases = [
+            ("0:11", VoteType::Committee, 11),
+            ("1:22", VoteType::Witness, 22),
+            ("2:205", VoteType::Worker, 205),
+            ("7:3", VoteType::Other(7), 3)
+        ];
+
+        for (text, vote_type, instance) in cases {
+            let vote: VoteId = text.parse().unwrap();
+
+            assert_eq!(vote, VoteId::new(vote_type, instance));
+            assert_eq!(vote.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn vote_id_rejects_malformed_ids() {
+        for text in ["", "122", "1:", ":22", "a:1", "1:b", "1:-2", "256:1", "1:22:3", "1.22"] {
+            assert!(text.parse::<VoteId>().is_err(), "accepted {}", text);
+        }
+    }
+
+    #[test]
+    fn vote_ids_serialize_as_strings() {
+        let votes: Vec<VoteId> = serde_json::from_value(json!(["1:22", "0:11"])).unwrap();
+
+        assert_eq!(serde_json::to_value(&votes).unwrap(), json!(["1:22", "0:11"]));
+        assert!(serde_json::from_value::<VoteId>(json!(122)).is_err());
+        assert!(VoteId::new(VoteType::Committee, 50) < VoteId::new(VoteType::Witness, 1));
+    }
+
+    #[test]
+    fn witness_and_committee_member_from_node() {
+        let witness: Witness = serde_json::from_value(json!({
+            "id": "1.6.69",
+            "witness_account": "1.2.1234",
+            "last_aslot": 62273512,
+            "signing_key": "BTS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV",
+            "pay_vb": "1.13.900",
+            "vote_id": "1:210",
+            "total_votes": "61395773409245",
+            "url": "https://example.org",
+            "total_missed": 3516,
+            "last_confirmed_block_num": 62121954
+        })).unwrap();
+        let member: CommitteeMember = serde_json::from_value(json!({
+            "id": "1.5.22",
+            "committee_member_account": "1.2.1234",
+            "vote_id": "0:145",
+            "total_votes": 1200000000,
+            "url": ""
+        })).unwrap();
+
+        assert_eq!(witness.id, WitnessId::new(69));
+        assert_eq!(witness.vote_id, VoteId::new(VoteType::Witness, 210));
+        assert_eq!(witness.total_votes, 61395773409245);
+        assert_eq!(witness.pay_vb, Some(VestingBalanceId::new(900)));
+        assert_eq!(member.vote_id, VoteId::new(VoteType::Committee, 145));
+        assert_eq!(member.total_votes, 1200000000);
+    }
+
+    #[test]
+    fn worker_from_node() {
+        let worker: Worker = serde_json::from_value(worker()).unwrap();
+        let time = |text: &str| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S").unwrap();
+
+        assert_eq!(worker.id, WorkerId::new(205));
+        assert_eq!(worker.worker.0, 1);
+        assert_eq!(worker.vote_for, VoteId::new(VoteType::Worker, 410));
+        assert_eq!(worker.vote_against.instance, 411);
+        assert_eq!(worker.total_votes_for, 210000000000000);
+        assert!(worker.is_active(time("2021-06-01T00:00:00")));
+        assert!(!worker.is_active(time("2022-06-01T00:00:00")));
+        assert!(!worker.is_active(time("2021-05-31T23:59:59")));
+    }
+
+    #[test]
+    fn worker_with_malformed_vote_is_rejected() {
+        let mut value = worker();
+        value["vote_for"] = json!("410");
+
+        assert!(serde_json::from_value::<Worker>(value).is_err());
+    }
+}
\ No newline at end of file
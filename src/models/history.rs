@@ -0,0 +1,220 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::models::asset::{AssetAmount, Price, deserialize_share_amount};
+use crate::models::market::Decimal;
+use crate::models::object_id::{AccountId, AssetId, ObjectId, OperationHistoryId};
+use crate::models::transaction::{Operation, OperationResult};
+
+pub const MAX_TRADE_HISTORY_LIMIT: u32 = 100;
+pub const MAX_MARKET_HISTORY_BUCKETS: usize = 200;
+pub const ACCOUNT_HISTORY_PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketTrade {
+    pub sequence: i64,
+    pub date: NaiveDateTime,
+    pub price: Decimal,
+    pub amount: Decimal,
+    pub value: Decimal,
+    #[serde(default, rename = "type")]
+    pub trade_type: Option<String>,
+    #[serde(default)]
+    pub side1_account_id: Option<AccountId>,
+    #[serde(default)]
+    pub side2_account_id: Option<AccountId>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketKey {
+    pub base: AssetId,
+    pub quote: AssetId,
+    pub seconds: u32,
+    pub open: NaiveDateTime
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketBucket {
+    pub id: ObjectId,
+    pub key: BucketKey,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub high_base: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub high_quote: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub low_base: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub low_quote: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub open_base: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub open_quote: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub close_base: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub close_quote: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub base_volume: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub quote_volume: i64
+}
+
+impl MarketBucket {
+    fn price(&self, base: i64, quote: i64) -> Price {
+        return Price {
+            base: AssetAmount { amount: base, asset_id: self.key.base },
+            quote: AssetAmount { amount: quote, asset_id: self.key.quote }
+        };
+    }
+
+    pub fn open_price(&self) -> Price {
+        return self.price(self.open_base, self.open_quote);
+    }
+
+    pub fn high_price(&self) -> Price {
+        return self.price(self.high_base, self.high_quote);
+    }
+
+    pub fn low_price(&self) -> Price {
+        return self.price(self.low_base, self.low_quote);
+    }
+
+    pub fn close_price(&self) -> Price {
+        return self.price(self.close_base, self.close_quote);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationHistory {
+    pub id: OperationHistoryId,
+    pub op: Operation,
+    pub result: OperationResult,
+    pub block_num: u32,
+    pub trx_in_block: u16,
+    pub op_in_trx: u16,
+    pub virtual_op: u32,
+    #[serde(default)]
+    pub is_virtual: bool,
+    #[serde(default)]
+    pub block_time: Option<NaiveDateTime>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationHistoryDetail {
+    pub total_count: u32,
+    pub operation_history_objs: Vec<OperationHistory>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn market_trade_from_node() {
+        let trade: MarketTrade = serde_json::from_value(json!({
+            "sequence": 3385620,
+            "date": "2021-11-20T10:15:42",
+            "price": "0.0251836",
+            "amount": "1191.4221",
+            "value": "30.00474",
+            "type": "sell",
+            "side1_account_id": "1.2.1619533",
+            "side2_account_id": "1.2.883283"
+        })).unwrap();
+
+        assert_eq!(trade.sequence, 3385620);
+        assert_eq!(trade.price.to_string(), "0.0251836");
+        assert_eq!(trade.amount.to_amount(4), Some(11914221));
+        assert_eq!(trade.trade_type.as_deref(), Some("sell"));
+        assert_eq!(trade.side2_account_id, Some(AccountId::new(883283)));
+    }
+
+    #[test]
+    fn market_bucket_prices() {
+        let bucket: MarketBucket = serde_json::from_value(json!({
+            "id": "5.1.6217643",
+            "key": {"base": "1.3.0", "quote": "1.3.113", "seconds": 86400, "open": "2021-11-20T00:00:00"},
+            "high_base": "3987650",
+            "high_quote": 100000,
+            "low_base": 3875000,
+            "low_quote": 100000,
+            "open_base": 3925000,
+            "open_quote": 100000,
+            "close_base": 3950000,
+            "close_quote": "100000",
+            "base_volume": "128573362591",
+            "quote_volume": "3262108765"
+        })).unwrap();
+
+        assert_eq!(bucket.key.seconds, 86400);
+        assert_eq!(bucket.base_volume, 128573362591);
+        assert_eq!(bucket.high_price().base, AssetAmount { amount: 3987650, asset_id: AssetId::new(0) });
+        assert_eq!(bucket.close_price().quote, AssetAmount { amount: 100000, asset_id: AssetId::new(113) });
+        assert_eq!(bucket.open_price().base.amount, 3925000);
+        assert_eq!(bucket.low_price().base.amount, 3875000);
+    }
+
+    #[test]
+    fn virtual_fill_operation_from_account_history() {
+        let operation: OperationHistory = serde_json::from_value(json!({
+            "id": "1.11.1234567890",
+            "op": [4, {
+                "fee": {"amount": 0, "asset_id": "1.3.113"},
+                "order_id": "1.7.532713466",
+                "account_id": "1.2.1619533",
+                "pays": {"amount": "30004740", "asset_id": "1.3.0"},
+                "receives": {"amount": 11914221, "asset_id": "1.3.113"},
+                "fill_price": {
+                    "base": {"amount": "100000000", "asset_id": "1.3.0"},
+                    "quote": {"amount": 3970835, "asset_id": "1.3.113"}
+                },
+                "is_maker": true
+            }],
+            "result": [0, {}],
+            "block_num": 62121954,
+            "trx_in_block": 3,
+            "op_in_trx": 0,
+            "virtual_op": 15,
+            "is_virtual": true,
+            "block_time": "2021-11-20T10:15:42"
+        })).unwrap();
+
+        assert_eq!(operation.id, OperationHistoryId::new(1234567890));
+        assert_eq!(operation.op.get_type(), 4);
+        assert_eq!(operation.op.involved_accounts().into_iter().collect::<Vec<_>>(), vec![AccountId::new(1619533)]);
+        assert_eq!(operation.result.get_type(), 0);
+        assert!(operation.is_virtual);
+        assert_eq!(operation.block_time.map(|time| time.to_string()).as_deref(), Some("2021-11-20 10:15:42"));
+    }
+
+    #[test]
+    fn operation_history_detail_without_optional_fields() {
+        let detail: OperationHistoryDetail = serde_json::from_value(json!({
+            "total_count": 2,
+            "operation_history_objs": [{
+                "id": "1.11.10",
+                "op": [1, {
+                    "fee": {"amount": 482, "asset_id": "1.3.0"},
+                    "seller": "1.2.100",
+                    "amount_to_sell": {"amount": 100000, "asset_id": "1.3.0"},
+                    "min_to_receive": {"amount": 2500, "asset_id": "1.3.113"},
+                    "expiration": "2021-11-27T10:15:39",
+                    "fill_or_kill": false,
+                    "extensions": []
+                }],
+                "result": [1, "1.7.123"],
+                "block_num": 100,
+                "trx_in_block": 0,
+                "op_in_trx": 0,
+                "virtual_op": 0
+            }]
+        })).unwrap();
+        let operation = &detail.operation_history_objs[0];
+
+        assert_eq!(detail.total_count, 2);
+        assert!(!operation.is_virtual);
+        assert!(operation.block_time.is_none());
+        assert_eq!(operation.result.get_object_id(), Some("1.7.123".parse().unwrap()));
+    }
+}
\ No newline at end of file
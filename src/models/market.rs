@@ -0,0 +1,484 @@
+use std::fmt;
+use std::str::FromStr;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::models::asset::{Asset, AssetAmount, Price, deserialize_share_amount};
+use crate::models::objects::ChainObject;
+use crate::models::object_id::{AccountId, CallOrderId, CollateralBidId, ForceSettlementId, LimitOrderId, ObjectId};
+
+pub const FILL_ORDER_OPERATION: u8 = 4;
+
+const MAX_DECIMAL_SCALE: u32 = 38;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillOrder {
+    pub fee: AssetAmount,
+    pub order_id: ObjectId,
+    pub account_id: AccountId,
+    pub pays: AssetAmount,
+    pub receives: AssetAmount,
+    #[serde(default)]
+    pub fill_price: Option<Price>,
+    #[serde(default)]
+    pub is_maker: Option<bool>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitOrder {
+    pub id: LimitOrderId,
+    pub expiration: NaiveDateTime,
+    pub seller: AccountId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub for_sale: i64,
+    pub sell_price: Price,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub deferred_fee: i64,
+    #[serde(default)]
+    pub deferred_paid_fee: Option<AssetAmount>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallOrder {
+    pub id: CallOrderId,
+    pub borrower: AccountId,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub collateral: i64,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub debt: i64,
+    pub call_price: Price,
+    #[serde(default)]
+    pub target_collateral_ratio: Option<u16>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceSettlement {
+    pub id: ForceSettlementId,
+    pub owner: AccountId,
+    pub balance: AssetAmount,
+    pub settlement_date: NaiveDateTime
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MarketUpdate {
+    pub fills: Vec<FillOrder>,
+    pub orders: Vec<ChainObject>,
+    pub removed: Vec<ObjectId>,
+    pub unknown: Vec<Value>
+}
+
+impl MarketUpdate {
+    fn parse_fill(entry: &Value) -> Option<FillOrder> {
+        let entries = entry.as_array()?;
+
+        let operation = if entries.first()?.is_array() {
+            entries.first()?.as_array()?
+        } else {
+            entries
+        };
+
+        if operation.first()?.as_u64()? != FILL_ORDER_OPERATION as u64 {
+            return None;
+        }
+
+        return serde_json::from_value(operation.get(1)?.clone()).ok();
+    }
+
+    pub fn from_notice(notice: Value) -> Option<MarketUpdate> {
+        let mut update = MarketUpdate::default();
+
+        let entries = notice.as_array()?;
+
+        for entry in entries {
+            let items = if entry.as_array().map(|items| items.iter().all(|item| !item.is_number())).unwrap_or(false) {
+                entry.as_array()?.clone()
+            } else {
+                vec![entry.clone()]
+            };
+
+            for item in items {
+                if let Some(removed_id) = item.as_str().and_then(|id| id.parse::<ObjectId>().ok()) {
+                    update.removed.push(removed_id);
+                } else if item.is_object() {
+                    update.orders.push(ChainObject::from_value(item));
+                } else if let Some(fill) = MarketUpdate::parse_fill(&item) {
+                    update.fills.push(fill);
+                } else {
+                    update.unknown.push(item);
+                }
+            }
+        }
+
+        return Some(update);
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Decimal {
+    pub mantissa: i128,
+    pub scale: u32
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    return a.abs();
+}
+
+fn asset_amount(amount: i128, asset: &Asset) -> Option<AssetAmount> {
+    return Some(AssetAmount { amount: i64::try_from(amount).ok()?, asset_id: asset.id });
+}
+
+impl Decimal {
+    pub fn to_f64(&self) -> f64 {
+        return self.mantissa as f64 / 10f64.powi(self.scale as i32);
+    }
+
+    pub fn to_amount(&self, precision: u8) -> Option<i64> {
+        let precision = precision as u32;
+
+        let amount = if precision >= self.scale {
+            self.mantissa.checked_mul(10i128.checked_pow(precision - self.scale)?)?
+        } else {
+            let divisor = 10i128.checked_pow(self.scale - precision)?;
+            if self.mantissa % divisor != 0 {
+                return None;
+            }
+            self.mantissa / divisor
+        };
+
+        return i64::try_from(amount).ok();
+    }
+
+    pub fn to_price(&self, base: &Asset, quote: &Asset) -> Option<Price> {
+        let base_amount = self.mantissa.checked_mul(10i128.checked_pow(base.precision as u32)?)?;
+        let quote_amount = 10i128.checked_pow(self.scale.checked_add(quote.precision as u32)?)?;
+        let divisor = gcd(base_amount, quote_amount);
+
+        if divisor == 0 {
+            return None;
+        }
+
+        return Some(Price {
+            base: asset_amount(base_amount / divisor, base)?,
+            quote: asset_amount(quote_amount / divisor, quote)?
+        });
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.mantissa);
+        }
+
+        let scale = 10u128.pow(self.scale);
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let whole = self.mantissa.unsigned_abs() / scale;
+        let fraction = self.mantissa.unsigned_abs() % scale;
+
+        write!(f, "{}{}.{:0width$}", sign, whole, fraction, width = self.scale as usize)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+
+        let (number, exponent) = if let Some((number, exponent)) = text.split_once(['e', 'E']) {
+            (number, exponent.parse::<i32>().map_err(|_e| format!("invalid decimal: {}", s))?)
+        } else {
+            (text, 0)
+        };
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        let digits = format!("{}{}", whole, fraction);
+        let mantissa = digits.parse::<i128>().map_err(|_e| format!("invalid decimal: {}", s))?;
+
+        if fraction.chars().any(|c| !c.is_ascii_digit()) {
+            return Err(format!("invalid decimal: {}", s));
+        }
+
+        let scale = fraction.len() as i64 - exponent as i64;
+
+        if scale > MAX_DECIMAL_SCALE as i64 {
+            return Err(format!("invalid decimal: {}", s));
+        }
+
+        if scale >= 0 {
+            return Ok(Decimal { mantissa, scale: scale as u32 });
+        }
+
+        let mantissa = u32::try_from(-scale).ok()
+            .and_then(|shift| 10i128.checked_pow(shift))
+            .and_then(|factor| mantissa.checked_mul(factor))
+            .ok_or_else(|| format!("invalid decimal: {}", s))?;
+
+        return Ok(Decimal { mantissa, scale: 0 });
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+
+        match &value {
+            Value::String(text) => return text.parse().map_err(serde::de::Error::custom),
+            Value::Number(number) => return number.to_string().parse().map_err(serde::de::Error::custom),
+            _ => return Err(serde::de::Error::custom(format!("invalid decimal: {}", value)))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Order {
+    pub price: Decimal,
+    pub quote: Decimal,
+    pub base: Decimal,
+    #[serde(default)]
+    pub id: Option<LimitOrderId>,
+    #[serde(default)]
+    pub owner_id: Option<AccountId>,
+    #[serde(default)]
+    pub owner_name: Option<String>,
+    #[serde(default)]
+    pub expiration: Option<NaiveDateTime>
+}
+
+impl Order {
+    pub fn to_price(&self, base: &Asset, quote: &Asset) -> Option<Price> {
+        return Some(Price {
+            base: AssetAmount { amount: self.base.to_amount(base.precision)?, asset_id: base.id },
+            quote: AssetAmount { amount: self.quote.to_amount(quote.precision)?, asset_id: quote.id }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub base: String,
+    pub quote: String,
+    pub bids: Vec<Order>,
+    pub asks: Vec<Order>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollateralBid {
+    pub id: CollateralBidId,
+    pub bidder: AccountId,
+    pub inv_swan_price: Price
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketTicker {
+    pub time: NaiveDateTime,
+    pub base: String,
+    pub quote: String,
+    pub latest: Decimal,
+    pub lowest_ask: Decimal,
+    pub highest_bid: Decimal,
+    pub percent_change: Decimal,
+    pub base_volume: Decimal,
+    pub quote_volume: Decimal
+}
+
+impl MarketTicker {
+    pub fn latest_price(&self, base: &Asset, quote: &Asset) -> Option<Price> {
+        return self.latest.to_price(base, quote);
+    }
+
+    pub fn lowest_ask_price(&self, base: &Asset, quote: &Asset) -> Option<Price> {
+        return self.lowest_ask.to_price(base, quote);
+    }
+
+    pub fn highest_bid_price(&self, base: &Asset, quote: &Asset) -> Option<Price> {
+        return self.highest_bid.to_price(base, quote);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketVolume {
+    pub time: NaiveDateTime,
+    pub base: String,
+    pub quote: String,
+    pub base_volume: Decimal,
+    pub quote_volume: Decimal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fill() -> Value {
+        return json!({
+            "fee": {"amount": 0, "asset_id": "1.3.0"},
+            "order_id": "1.7.5",
+            "account_id": "1.2.7",
+            "pays": {"amount": 100, "asset_id": "1.3.0"},
+            "receives": {"amount": 200, "asset_id": "1.3.1"},
+            "is_maker": true
+        });
+    }
+
+    fn limit_order() -> Value {
+        return json!({
+            "id": "1.7.6",
+            "expiration": "2030-01-01T00:00:00",
+            "seller": "1.2.8",
+            "for_sale": 500,
+            "sell_price": {
+                "base": {"amount": 500, "asset_id": "1.3.0"},
+                "quote": {"amount": 1000, "asset_id": "1.3.1"}
+            },
+            "deferred_fee": 0
+        });
+    }
+
+    #[test]
+    fn notice_splits_fills_orders_and_removals() {
+        let notice = json!([
+            [[[4, fill()], [0, {}]], "1.7.9"],
+            limit_order()
+        ]);
+
+        let update = MarketUpdate::from_notice(notice).unwrap();
+
+        assert_eq!(update.fills.len(), 1);
+        assert_eq!(update.fills[0].account_id, AccountId::new(7));
+        assert_eq!(update.fills[0].is_maker, Some(true));
+        assert_eq!(update.removed, vec![ObjectId::new(1, 7, 9)]);
+        assert_eq!(update.orders.len(), 1);
+        assert!(update.unknown.is_empty());
+        assert!(matches!(&update.orders[0], ChainObject::LimitOrder(order) if order.seller == AccountId::new(8)));
+    }
+
+    #[test]
+    fn notice_accepts_bare_fill_operations() {
+        let update = MarketUpdate::from_notice(json!([[4, fill()]])).unwrap();
+
+        assert_eq!(update.fills.len(), 1);
+        assert_eq!(update.fills[0].order_id, ObjectId::new(1, 7, 5));
+    }
+
+    #[test]
+    fn notice_keeps_other_operations_as_unknown_and_rejects_non_arrays() {
+        let update = MarketUpdate::from_notice(json!([[[1, {"seller": "1.2.7"}], [1, "1.7.5"]]])).unwrap();
+
+        assert!(update.fills.is_empty());
+        assert!(update.orders.is_empty());
+        assert!(update.removed.is_empty());
+        assert_eq!(update.unknown, vec![json!([1, {"seller": "1.2.7"}]), json!([1, "1.7.5"])]);
+
+        assert!(MarketUpdate::from_notice(json!({"fills": []})).is_none());
+    }
+    #[test]
+    fn decimal_parses_plain_and_negative_strings() {
+        assert_eq!("12.345".parse::<Decimal>(), Ok(Decimal { mantissa: 12345, scale: 3 }));
+        assert_eq!("-12.345".parse::<Decimal>(), Ok(Decimal { mantissa: -12345, scale: 3 }));
+        assert_eq!("-0.05".parse::<Decimal>(), Ok(Decimal { mantissa: -5, scale: 2 }));
+        assert_eq!("42".parse::<Decimal>(), Ok(Decimal { mantissa: 42, scale: 0 }));
+        assert_eq!("-0.05".parse::<Decimal>().unwrap().to_string(), "-0.05");
+        assert_eq!("12.345".parse::<Decimal>().unwrap().to_string(), "12.345");
+    }
+
+    #[test]
+    fn decimal_parses_exponent_forms() {
+        assert_eq!("1e-05".parse::<Decimal>(), Ok(Decimal { mantissa: 1, scale: 5 }));
+        assert_eq!("-2.5E-3".parse::<Decimal>(), Ok(Decimal { mantissa: -25, scale: 4 }));
+        assert_eq!("1.5e3".parse::<Decimal>(), Ok(Decimal { mantissa: 1500, scale: 0 }));
+        assert_eq!("1.25e+1".parse::<Decimal>(), Ok(Decimal { mantissa: 125, scale: 1 }));
+        assert_eq!("1e-05".parse::<Decimal>().unwrap().to_string(), "0.00001");
+    }
+
+    #[test]
+    fn decimal_rejects_invalid_strings() {
+        for invalid in ["", "-", "abc", "1.2.3", "1.-5", "1e", "1e-x", "1e-40", "1e40"] {
+            assert!(invalid.parse::<Decimal>().is_err(), "{} should not parse", invalid);
+        }
+    }
+
+    #[test]
+    fn decimal_deserializes_strings_and_numbers() {
+        assert_eq!(serde_json::from_value::<Decimal>(json!("-1.50")).unwrap(), Decimal { mantissa: -150, scale: 2 });
+        assert_eq!(serde_json::from_value::<Decimal>(json!(0.00001)).unwrap(), Decimal { mantissa: 1, scale: 5 });
+        assert_eq!(serde_json::from_value::<Decimal>(json!(-0.00025)).unwrap(), Decimal { mantissa: -25, scale: 5 });
+        assert_eq!(serde_json::from_value::<Decimal>(json!(1e20)).unwrap(), Decimal { mantissa: 100000000000000000000, scale: 0 });
+        assert_eq!(serde_json::from_value::<Decimal>(json!(-7)).unwrap(), Decimal { mantissa: -7, scale: 0 });
+        assert!(serde_json::from_value::<Decimal>(json!(true)).is_err());
+    }
+    fn asset(id: &str, symbol: &str, precision: u8) -> Asset {
+        return serde_json::from_value(json!({
+            "id": id,
+            "symbol": symbol,
+            "precision": precision,
+            "issuer": "1.2.0",
+            "options": {
+                "max_supply": "1000000000000000",
+                "market_fee_percent": 0,
+                "max_market_fee": "1000000000000000",
+                "issuer_permissions": 0,
+                "flags": 0,
+                "core_exchange_rate": {
+                    "base": {"amount": 1, "asset_id": "1.3.0"},
+                    "quote": {"amount": 1, "asset_id": id}
+                },
+                "whitelist_authorities": [],
+                "blacklist_authorities": [],
+                "whitelist_markets": [],
+                "blacklist_markets": [],
+                "description": "",
+                "extensions": {}
+            },
+            "dynamic_asset_data_id": "2.3.0"
+        })).unwrap();
+    }
+
+    #[test]
+    fn order_exposes_rational_price() {
+        let bts = asset("1.3.0", "BTS", 5);
+        let cny = asset("1.3.113", "CNY", 4);
+
+        let order: Order = serde_json::from_value(json!({
+            "price": "0.02547",
+            "quote": "118.3600",
+            "base": "3.01463",
+            "id": "1.7.123",
+            "owner_id": "1.2.5",
+            "owner_name": "maker",
+            "expiration": "2030-01-01T00:00:00"
+        })).unwrap();
+
+        let price = order.to_price(&bts, &cny).unwrap();
+
+        assert_eq!(price.base, AssetAmount { amount: 301463, asset_id: bts.id });
+        assert_eq!(price.quote, AssetAmount { amount: 1183600, asset_id: cny.id });
+    }
+
+    #[test]
+    fn decimal_converts_to_amounts_and_prices() {
+        let bts = asset("1.3.0", "BTS", 5);
+        let cny = asset("1.3.113", "CNY", 4);
+
+        assert_eq!("1.23".parse::<Decimal>().unwrap().to_amount(5), Some(123000));
+        assert_eq!("-1.230000".parse::<Decimal>().unwrap().to_amount(5), Some(-123000));
+        assert_eq!("1.234567".parse::<Decimal>().unwrap().to_amount(5), None);
+
+        let price = "0.0254".parse::<Decimal>().unwrap().to_price(&bts, &cny).unwrap();
+
+        assert_eq!(price.base, AssetAmount { amount: 127, asset_id: bts.id });
+        assert_eq!(price.quote, AssetAmount { amount: 500, asset_id: cny.id });
+        assert_eq!(price.to_f64(5, 4), Some(0.0254));
+    }
+}
\ No newline at end of file
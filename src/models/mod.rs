@@ -0,0 +1,12 @@
+pub mod account;
+pub mod asset;
+pub mod balance;
+pub mod block;
+pub mod governance;
+pub mod history;
+pub mod market;
+pub mod object_id;
+pub mod objects;
+pub mod operations;
+pub mod properties;
+pub mod transaction;
\ No newline at end of file
@@ -0,0 +1,325 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub const PROTOCOL_IDS: u8 = 1;
+pub const IMPLEMENTATION_IDS: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectIdError {
+    InvalidFormat(String),
+    UnexpectedType(String, &'static str)
+}
+
+impl fmt::Display for ObjectIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjectIdError::InvalidFormat(id) => write!(f,"Invalid object id {:?}, expected space.type.instance", id),
+            ObjectIdError::UnexpectedType(id, expected) => write!(f,"Object id {} is not a {} id", id, expected),
+        }
+    }
+}
+
+impl Error for ObjectIdError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId {
+    space: u8,
+    type_id: u8,
+    instance: u64
+}
+
+impl ObjectId {
+    pub fn new(space: u8, type_id: u8, instance: u64) -> Self {
+        Self { space, type_id, instance }
+    }
+
+    pub fn space(&self) -> u8 {
+        return self.space;
+    }
+
+    pub fn type_id(&self) -> u8 {
+        return self.type_id;
+    }
+
+    pub fn instance(&self) -> u64 {
+        return self.instance;
+    }
+
+    pub fn is<T: ObjectType>(&self) -> bool {
+        return self.space == T::SPACE && self.type_id == T::TYPE_ID;
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.space, self.type_id, self.instance)
+    }
+}
+
+fn is_canonical_number(part: &str) -> bool {
+    return !part.is_empty()
+        && part.bytes().all(|byte| byte.is_ascii_digit())
+        && (part == "0" || !part.starts_with('0'));
+}
+
+impl FromStr for ObjectId {
+    type Err = ObjectIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('.').collect();
+
+        if parts.len() != 3 {
+            return Err(ObjectIdError::InvalidFormat(String::from(s)));
+        }
+
+        if !parts.iter().all(|part| is_canonical_number(part)) {
+            return Err(ObjectIdError::InvalidFormat(String::from(s)));
+        }
+
+        let space = parts[0].parse::<u8>().map_err(|_e| ObjectIdError::InvalidFormat(String::from(s)))?;
+        let type_id = parts[1].parse::<u8>().map_err(|_e| ObjectIdError::InvalidFormat(String::from(s)))?;
+        let instance = parts[2].parse::<u64>().map_err(|_e| ObjectIdError::InvalidFormat(String::from(s)))?;
+
+        return Ok(ObjectId::new(space, type_id, instance));
+    }
+}
+
+impl Serialize for ObjectId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        return id.parse().map_err(serde::de::Error::custom);
+    }
+}
+
+pub trait ObjectType {
+    const SPACE: u8;
+    const TYPE_ID: u8;
+    const NAME: &'static str;
+}
+
+pub struct TypedObjectId<T: ObjectType> {
+    instance: u64,
+    object_type: PhantomData<fn() -> T>
+}
+
+impl<T: ObjectType> TypedObjectId<T> {
+    pub fn new(instance: u64) -> Self {
+        Self { instance, object_type: PhantomData }
+    }
+
+    pub fn instance(&self) -> u64 {
+        return self.instance;
+    }
+
+    pub fn to_object_id(&self) -> ObjectId {
+        return ObjectId::new(T::SPACE, T::TYPE_ID, self.instance);
+    }
+}
+
+impl<T: ObjectType> Copy for TypedObjectId<T> {}
+
+impl<T: ObjectType> Clone for TypedObjectId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ObjectType> PartialEq for TypedObjectId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.instance == other.instance
+    }
+}
+
+impl<T: ObjectType> Eq for TypedObjectId<T> {}
+
+impl<T: ObjectType> Hash for TypedObjectId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instance.hash(state);
+    }
+}
+
+impl<T: ObjectType> PartialOrd for TypedObjectId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: ObjectType> Ord for TypedObjectId<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.instance.cmp(&other.instance)
+    }
+}
+
+impl<T: ObjectType> fmt::Debug for TypedObjectId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})", T::NAME, self.to_object_id())
+    }
+}
+
+impl<T: ObjectType> fmt::Display for TypedObjectId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_object_id())
+    }
+}
+
+impl<T: ObjectType> TryFrom<ObjectId> for TypedObjectId<T> {
+    type Error = ObjectIdError;
+
+    fn try_from(id: ObjectId) -> Result<Self, Self::Error> {
+        if id.is::<T>() {
+            return Ok(TypedObjectId::new(id.instance()));
+        } else {
+            return Err(ObjectIdError::UnexpectedType(id.to_string(), T::NAME));
+        }
+    }
+}
+
+impl<T: ObjectType> From<TypedObjectId<T>> for ObjectId {
+    fn from(id: TypedObjectId<T>) -> ObjectId {
+        id.to_object_id()
+    }
+}
+
+impl<T: ObjectType> FromStr for TypedObjectId<T> {
+    type Err = ObjectIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return TypedObjectId::try_from(s.parse::<ObjectId>()?);
+    }
+}
+
+impl<T: ObjectType> Serialize for TypedObjectId<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: ObjectType> Deserialize<'de> for TypedObjectId<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        return id.parse().map_err(serde::de::Error::custom);
+    }
+}
+
+macro_rules! object_types {
+    ($($marker:ident, $alias:ident, $space:expr, $type_id:expr, $name:expr;)*) => {
+        $(
+            pub enum $marker {}
+
+            impl ObjectType for $marker {
+                const SPACE: u8 = $space;
+                const TYPE_ID: u8 = $type_id;
+                const NAME: &'static str = $name;
+            }
+
+            pub type $alias = TypedObjectId<$marker>;
+        )*
+    };
+}
+
+object_types! {
+    AccountType, AccountId, PROTOCOL_IDS, 2, "account";
+    AssetType, AssetId, PROTOCOL_IDS, 3, "asset";
+    ForceSettlementType, ForceSettlementId, PROTOCOL_IDS, 4, "force_settlement";
+    CommitteeMemberType, CommitteeMemberId, PROTOCOL_IDS, 5, "committee_member";
+    WitnessType, WitnessId, PROTOCOL_IDS, 6, "witness";
+    LimitOrderType, LimitOrderId, PROTOCOL_IDS, 7, "limit_order";
+    CallOrderType, CallOrderId, PROTOCOL_IDS, 8, "call_order";
+    CustomType, CustomId, PROTOCOL_IDS, 9, "custom";
+    ProposalType, ProposalId, PROTOCOL_IDS, 10, "proposal";
+    OperationHistoryType, OperationHistoryId, PROTOCOL_IDS, 11, "operation_history";
+    WithdrawPermissionType, WithdrawPermissionId, PROTOCOL_IDS, 12, "withdraw_permission";
+    VestingBalanceType, VestingBalanceId, PROTOCOL_IDS, 13, "vesting_balance";
+    WorkerType, WorkerId, PROTOCOL_IDS, 14, "worker";
+    BalanceType, BalanceId, PROTOCOL_IDS, 15, "balance";
+    HtlcType, HtlcId, PROTOCOL_IDS, 16, "htlc";
+    CustomAuthorityType, CustomAuthorityId, PROTOCOL_IDS, 17, "custom_authority";
+    TicketType, TicketId, PROTOCOL_IDS, 18, "ticket";
+    LiquidityPoolType, LiquidityPoolId, PROTOCOL_IDS, 19, "liquidity_pool";
+    SametFundType, SametFundId, PROTOCOL_IDS, 20, "samet_fund";
+    CreditOfferType, CreditOfferId, PROTOCOL_IDS, 21, "credit_offer";
+    CreditDealType, CreditDealId, PROTOCOL_IDS, 22, "credit_deal";
+    GlobalPropertyType, GlobalPropertyId, IMPLEMENTATION_IDS, 0, "global_property";
+    DynamicGlobalPropertyType, DynamicGlobalPropertyId, IMPLEMENTATION_IDS, 1, "dynamic_global_property";
+    AssetDynamicDataType, AssetDynamicDataId, IMPLEMENTATION_IDS, 3, "asset_dynamic_data";
+    AssetBitassetDataType, AssetBitassetDataId, IMPLEMENTATION_IDS, 4, "asset_bitasset_data";
+    AccountBalanceType, AccountBalanceId, IMPLEMENTATION_IDS, 5, "account_balance";
+    AccountStatisticsType, AccountStatisticsId, IMPLEMENTATION_IDS, 6, "account_statistics";
+    TransactionHistoryType, TransactionHistoryId, IMPLEMENTATION_IDS, 7, "transaction_history";
+    BlockSummaryType, BlockSummaryId, IMPLEMENTATION_IDS, 8, "block_summary";
+    AccountTransactionHistoryType, AccountTransactionHistoryId, IMPLEMENTATION_IDS, 9, "account_transaction_history";
+    BlindedBalanceType, BlindedBalanceId, IMPLEMENTATION_IDS, 10, "blinded_balance";
+    ChainPropertyType, ChainPropertyId, IMPLEMENTATION_IDS, 11, "chain_property";
+    WitnessScheduleType, WitnessScheduleId, IMPLEMENTATION_IDS, 12, "witness_schedule";
+    BudgetRecordType, BudgetRecordId, IMPLEMENTATION_IDS, 13, "budget_record";
+    SpecialAuthorityType, SpecialAuthorityId, IMPLEMENTATION_IDS, 14, "special_authority";
+    BuybackType, BuybackId, IMPLEMENTATION_IDS, 15, "buyback";
+    FbaAccumulatorType, FbaAccumulatorId, IMPLEMENTATION_IDS, 16, "fba_accumulator";
+    CollateralBidType, CollateralBidId, IMPLEMENTATION_IDS, 17, "collateral_bid";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn object_id_round_trips_through_strings() {
+        let id = "1.2.12345".parse::<ObjectId>().unwrap();
+
+        assert_eq!(id, ObjectId::new(1, 2, 12345));
+        assert_eq!(id.to_string(), "1.2.12345");
+        assert!(id.is::<AccountType>());
+        assert_eq!("2.0.0".parse::<ObjectId>(), Ok(ObjectId::new(2, 0, 0)));
+    }
+
+    #[test]
+    fn object_id_rejects_malformed_strings() {
+        for malformed in ["", "1.2", "1.2.3.4", "1.2.x", "a.2.3", "1.256.3", "1.2.-3", "1..3", " 1.2.3", "1.2.+7", "+1.2.7", "1.2.007", "01.2.7", "1.02.7", "1.2.", "1.2.7 ", "1.2.٣"] {
+            assert_eq!(malformed.parse::<ObjectId>(), Err(ObjectIdError::InvalidFormat(String::from(malformed))));
+        }
+    }
+
+    #[test]
+    fn typed_id_round_trips_through_strings() {
+        let account = "1.2.7".parse::<AccountId>().unwrap();
+
+        assert_eq!(account, AccountId::new(7));
+        assert_eq!(account.to_string(), "1.2.7");
+        assert_eq!(ObjectId::from(account), ObjectId::new(1, 2, 7));
+    }
+
+    #[test]
+    fn typed_id_rejects_wrong_type_and_malformed_strings() {
+        assert_eq!("1.3.0".parse::<AccountId>(), Err(ObjectIdError::UnexpectedType(String::from("1.3.0"), "account")));
+        assert_eq!("2.2.7".parse::<AccountId>(), Err(ObjectIdError::UnexpectedType(String::from("2.2.7"), "account")));
+        assert_eq!("1.2".parse::<AccountId>(), Err(ObjectIdError::InvalidFormat(String::from("1.2"))));
+        assert!(AccountId::try_from(ObjectId::new(1, 3, 0)).is_err());
+    }
+
+    #[test]
+    fn ids_round_trip_through_serde() {
+        let account: AccountId = serde_json::from_value(json!("1.2.7")).unwrap();
+        let asset: AssetId = serde_json::from_value(json!("1.3.0")).unwrap();
+        let object: ObjectId = serde_json::from_value(json!("2.1.0")).unwrap();
+
+        assert_eq!(serde_json::to_value(account).unwrap(), json!("1.2.7"));
+        assert_eq!(serde_json::to_value(asset).unwrap(), json!("1.3.0"));
+        assert_eq!(serde_json::to_value(object).unwrap(), json!("2.1.0"));
+
+        assert!(serde_json::from_value::<AccountId>(json!("1.3.0")).is_err());
+        assert!(serde_json::from_value::<AccountId>(json!(7)).is_err());
+        assert!(serde_json::from_value::<ObjectId>(json!("1.2")).is_err());
+    }
+}
\ No newline at end of file
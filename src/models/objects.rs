@@ -0,0 +1,167 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::client::errors::GrapheneError;
+use crate::models::account::{Account, AccountBalance};
+use crate::models::asset::{Asset, AssetBitassetData, AssetDynamicData};
+use crate::models::balance::{BalanceObject, VestingBalance};
+use crate::models::governance::{CommitteeMember, Witness, Worker};
+use crate::models::market::{CallOrder, ForceSettlement, LimitOrder};
+use crate::models::properties::{ChainProperties, DynamicGlobalProperties, GlobalProperties};
+use crate::models::object_id::{
+    AccountBalanceType, AccountType, AssetBitassetDataType, AssetDynamicDataType, AssetType, BalanceType,
+    CallOrderType, ChainPropertyType, CommitteeMemberType, DynamicGlobalPropertyType, ForceSettlementType,
+    GlobalPropertyType, LimitOrderType, ObjectId, VestingBalanceType, WitnessType, WorkerType
+};
+
+#[derive(Debug, Clone)]
+pub enum ChainObject {
+    Account(Box<Account>),
+    AccountBalance(AccountBalance),
+    Asset(Box<Asset>),
+    AssetDynamicData(AssetDynamicData),
+    AssetBitassetData(Box<AssetBitassetData>),
+    LimitOrder(LimitOrder),
+    CallOrder(CallOrder),
+    ForceSettlement(ForceSettlement),
+    Witness(Witness),
+    CommitteeMember(CommitteeMember),
+    Worker(Box<Worker>),
+    VestingBalance(VestingBalance),
+    Balance(BalanceObject),
+    GlobalProperties(Box<GlobalProperties>),
+    DynamicGlobalProperties(Box<DynamicGlobalProperties>),
+    ChainProperties(ChainProperties),
+    Invalid(Value, GrapheneError),
+    Other(Value)
+}
+
+impl ChainObject {
+    fn parse<T: for<'de> Deserialize<'de>>(object: &Value, wrap: fn(T) -> ChainObject) -> ChainObject {
+        match serde_json::from_value::<T>(object.clone()) {
+            Ok(parsed) => return wrap(parsed),
+            Err(error) => {
+                let error = GrapheneError::Serialization(format!("chain object {}: {}", object.get("id").unwrap_or(&Value::Null), error));
+                return ChainObject::Invalid(object.clone(), error);
+            }
+        }
+    }
+
+    pub fn from_value(object: Value) -> ChainObject {
+        let id = if let Some(id) = object.get("id").and_then(|id| id.as_str()).and_then(|id| id.parse::<ObjectId>().ok()) {
+            id
+        } else {
+            return ChainObject::Other(object);
+        };
+
+        if id.is::<AccountType>() {
+            return ChainObject::parse(&object, |account| ChainObject::Account(Box::new(account)));
+        } else if id.is::<AccountBalanceType>() {
+            return ChainObject::parse(&object, ChainObject::AccountBalance);
+        } else if id.is::<AssetType>() {
+            return ChainObject::parse(&object, |asset| ChainObject::Asset(Box::new(asset)));
+        } else if id.is::<AssetDynamicDataType>() {
+            return ChainObject::parse(&object, ChainObject::AssetDynamicData);
+        } else if id.is::<AssetBitassetDataType>() {
+            return ChainObject::parse(&object, |bitasset| ChainObject::AssetBitassetData(Box::new(bitasset)));
+        } else if id.is::<LimitOrderType>() {
+            return ChainObject::parse(&object, ChainObject::LimitOrder);
+        } else if id.is::<CallOrderType>() {
+            return ChainObject::parse(&object, ChainObject::CallOrder);
+        } else if id.is::<ForceSettlementType>() {
+            return ChainObject::parse(&object, ChainObject::ForceSettlement);
+        } else if id.is::<WitnessType>() {
+            return ChainObject::parse(&object, ChainObject::Witness);
+        } else if id.is::<CommitteeMemberType>() {
+            return ChainObject::parse(&object, ChainObject::CommitteeMember);
+        } else if id.is::<WorkerType>() {
+            return ChainObject::parse(&object, |worker| ChainObject::Worker(Box::new(worker)));
+        } else if id.is::<VestingBalanceType>() {
+            return ChainObject::parse(&object, ChainObject::VestingBalance);
+        } else if id.is::<BalanceType>() {
+            return ChainObject::parse(&object, ChainObject::Balance);
+        } else if id.is::<GlobalPropertyType>() {
+            return ChainObject::parse(&object, |properties| ChainObject::GlobalProperties(Box::new(properties)));
+        } else if id.is::<DynamicGlobalPropertyType>() {
+            return ChainObject::parse(&object, |properties| ChainObject::DynamicGlobalProperties(Box::new(properties)));
+        } else if id.is::<ChainPropertyType>() {
+            return ChainObject::parse(&object, ChainObject::ChainProperties);
+        } else {
+            return ChainObject::Other(object);
+        }
+    }
+
+    pub fn get_id(&self) -> Option<ObjectId> {
+        match self {
+            ChainObject::Account(object) => Some(object.id.into()),
+            ChainObject::AccountBalance(object) => Some(object.id.into()),
+            ChainObject::Asset(object) => Some(object.id.into()),
+            ChainObject::AssetDynamicData(object) => Some(object.id.into()),
+            ChainObject::AssetBitassetData(object) => Some(object.id.into()),
+            ChainObject::LimitOrder(object) => Some(object.id.into()),
+            ChainObject::CallOrder(object) => Some(object.id.into()),
+            ChainObject::ForceSettlement(object) => Some(object.id.into()),
+            ChainObject::Witness(object) => Some(object.id.into()),
+            ChainObject::CommitteeMember(object) => Some(object.id.into()),
+            ChainObject::Worker(object) => Some(object.id.into()),
+            ChainObject::VestingBalance(object) => Some(object.id.into()),
+            ChainObject::Balance(object) => Some(object.id.into()),
+            ChainObject::GlobalProperties(object) => Some(object.id.into()),
+            ChainObject::DynamicGlobalProperties(object) => Some(object.id.into()),
+            ChainObject::ChainProperties(object) => Some(object.id.into()),
+            ChainObject::Invalid(object, _) | ChainObject::Other(object) => object.get("id").and_then(|id| id.as_str()).and_then(|id| id.parse().ok())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let object = Value::deserialize(deserializer)?;
+        return Ok(ChainObject::from_value(object));
+    }
+}
+
+impl Serialize for ChainObject {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ChainObject::Account(object) => object.serialize(serializer),
+            ChainObject::AccountBalance(object) => object.serialize(serializer),
+            ChainObject::Asset(object) => object.serialize(serializer),
+            ChainObject::AssetDynamicData(object) => object.serialize(serializer),
+            ChainObject::AssetBitassetData(object) => object.serialize(serializer),
+            ChainObject::LimitOrder(object) => object.serialize(serializer),
+            ChainObject::CallOrder(object) => object.serialize(serializer),
+            ChainObject::ForceSettlement(object) => object.serialize(serializer),
+            ChainObject::Witness(object) => object.serialize(serializer),
+            ChainObject::CommitteeMember(object) => object.serialize(serializer),
+            ChainObject::Worker(object) => object.serialize(serializer),
+            ChainObject::VestingBalance(object) => object.serialize(serializer),
+            ChainObject::Balance(object) => object.serialize(serializer),
+            ChainObject::GlobalProperties(object) => object.serialize(serializer),
+            ChainObject::DynamicGlobalProperties(object) => object.serialize(serializer),
+            ChainObject::ChainProperties(object) => object.serialize(serializer),
+            ChainObject::Invalid(object, _) | ChainObject::Other(object) => object.serialize(serializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn malformed_object_carries_parse_error() {
+        let object = ChainObject::from_value(json!({"id": "1.2.7", "name": 42}));
+
+        assert!(matches!(&object, ChainObject::Invalid(_, GrapheneError::Serialization(error)) if error.contains("1.2.7")));
+        assert_eq!(object.get_id(), "1.2.7".parse().ok());
+    }
+
+    #[test]
+    fn unknown_object_type_is_other() {
+        let object = ChainObject::from_value(json!({"id": "2.20.5", "owner": "1.2.7"}));
+
+        assert!(matches!(object, ChainObject::Other(_)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,110 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::errors::GrapheneError;
+use crate::models::asset::AssetAmount;
+use crate::models::market::{FILL_ORDER_OPERATION, FillOrder};
+use crate::models::object_id::{AccountId, LimitOrderId};
+
+pub const TRANSFER_OPERATION: u8 = 0;
+pub const LIMIT_ORDER_CREATE_OPERATION: u8 = 1;
+pub const LIMIT_ORDER_CANCEL_OPERATION: u8 = 2;
+pub const CALL_ORDER_UPDATE_OPERATION: u8 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferOperation {
+    pub fee: AssetAmount,
+    pub from: AccountId,
+    pub to: AccountId,
+    pub amount: AssetAmount,
+    #[serde(default)]
+    pub memo: Option<Value>,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitOrderCreateOperation {
+    pub fee: AssetAmount,
+    pub seller: AccountId,
+    pub amount_to_sell: AssetAmount,
+    pub min_to_receive: AssetAmount,
+    pub expiration: NaiveDateTime,
+    pub fill_or_kill: bool,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitOrderCancelOperation {
+    pub fee: AssetAmount,
+    pub fee_paying_account: AccountId,
+    pub order: LimitOrderId,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallOrderUpdateOperation {
+    pub fee: AssetAmount,
+    pub funding_account: AccountId,
+    pub delta_collateral: AssetAmount,
+    pub delta_debt: AssetAmount,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone)]
+pub enum OperationPayload {
+    Transfer(TransferOperation),
+    LimitOrderCreate(LimitOrderCreateOperation),
+    LimitOrderCancel(LimitOrderCancelOperation),
+    CallOrderUpdate(CallOrderUpdateOperation),
+    FillOrder(FillOrder),
+    Invalid(u8, Value, GrapheneError),
+    Other(u8, Value)
+}
+
+impl OperationPayload {
+    fn parse<T: for<'de> Deserialize<'de>>(operation_type: u8, payload: &Value, wrap: fn(T) -> OperationPayload) -> OperationPayload {
+        match serde_json::from_value::<T>(payload.clone()) {
+            Ok(parsed) => return wrap(parsed),
+            Err(error) => {
+                let error = GrapheneError::Serialization(format!("operation {}: {}", operation_type, error));
+                return OperationPayload::Invalid(operation_type, payload.clone(), error);
+            }
+        }
+    }
+
+    pub fn from_operation(operation_type: u8, payload: &Value) -> OperationPayload {
+        match operation_type {
+            TRANSFER_OPERATION => return OperationPayload::parse(operation_type, payload, OperationPayload::Transfer),
+            LIMIT_ORDER_CREATE_OPERATION => return OperationPayload::parse(operation_type, payload, OperationPayload::LimitOrderCreate),
+            LIMIT_ORDER_CANCEL_OPERATION => return OperationPayload::parse(operation_type, payload, OperationPayload::LimitOrderCancel),
+            CALL_ORDER_UPDATE_OPERATION => return OperationPayload::parse(operation_type, payload, OperationPayload::CallOrderUpdate),
+            FILL_ORDER_OPERATION => return OperationPayload::parse(operation_type, payload, OperationPayload::FillOrder),
+            _ => return OperationPayload::Other(operation_type, payload.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn malformed_operation_carries_parse_error() {
+        let payload = OperationPayload::from_operation(TRANSFER_OPERATION, &json!({"from": "1.2.7"}));
+
+        assert!(matches!(payload, OperationPayload::Invalid(TRANSFER_OPERATION, _, GrapheneError::Serialization(_))));
+    }
+
+    #[test]
+    fn unknown_operation_is_other() {
+        let payload = OperationPayload::from_operation(5, &json!({"registrar": "1.2.7"}));
+
+        assert!(matches!(payload, OperationPayload::Other(5, _)));
+    }
+}
\ No newline at end of file
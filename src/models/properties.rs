@@ -0,0 +1,314 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::models::asset::deserialize_share_amount;
+use crate::models::object_id::{
+    ChainPropertyId, CommitteeMemberId, DynamicGlobalPropertyId, GlobalPropertyId, WitnessId
+};
+
+pub const GRAPHENE_100_PERCENT: u32 = 10000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeParameters(pub u8, pub Map<String, Value>);
+
+impl FeeParameters {
+    pub fn get_operation_type(&self) -> u8 {
+        return self.0;
+    }
+
+    pub fn get_field(&self, field: &str) -> Option<i64> {
+        match self.1.get(field) {
+            Some(Value::Number(number)) => return number.as_i64(),
+            Some(Value::String(text)) => return text.parse::<i64>().ok(),
+            _ => return None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    pub parameters: Vec<FeeParameters>,
+    pub scale: u32
+}
+
+impl FeeSchedule {
+    pub fn get_parameters(&self, operation_type: u8) -> Option<&FeeParameters> {
+        return self.parameters.iter().find(|parameters| parameters.get_operation_type() == operation_type);
+    }
+
+    pub fn get_scaled_fee(&self, operation_type: u8, field: &str) -> Option<i64> {
+        let fee = self.get_parameters(operation_type)?.get_field(field)?;
+        return Some(((fee as i128 * self.scale as i128) / GRAPHENE_100_PERCENT as i128) as i64);
+    }
+
+    pub fn get_fee(&self, operation_type: u8) -> Option<i64> {
+        return self.get_scaled_fee(operation_type, "fee");
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainParameters {
+    pub current_fees: FeeSchedule,
+    pub block_interval: u8,
+    pub maintenance_interval: u32,
+    pub maintenance_skip_slots: u8,
+    pub committee_proposal_review_period: u32,
+    pub maximum_transaction_size: u32,
+    pub maximum_block_size: u32,
+    pub maximum_time_until_expiration: u32,
+    pub maximum_proposal_lifetime: u32,
+    pub maximum_asset_whitelist_authorities: u8,
+    pub maximum_asset_feed_publishers: u8,
+    pub maximum_witness_count: u16,
+    pub maximum_committee_count: u16,
+    pub maximum_authority_membership: u16,
+    pub reserve_percent_of_fee: u16,
+    pub network_percent_of_fee: u16,
+    pub lifetime_referrer_percent_of_fee: u16,
+    pub cashback_vesting_period_seconds: u32,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub cashback_vesting_threshold: i64,
+    pub count_non_member_votes: bool,
+    pub allow_non_member_whitelists: bool,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub witness_pay_per_block: i64,
+    pub witness_pay_vesting_seconds: u32,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub worker_budget_per_day: i64,
+    pub max_predicate_opcode: u16,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub fee_liquidation_threshold: i64,
+    pub accounts_per_fee_scale: u16,
+    pub account_fee_scale_bitshifts: u8,
+    pub max_authority_depth: u8,
+    #[serde(default)]
+    pub extensions: Value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalProperties {
+    pub id: GlobalPropertyId,
+    pub parameters: ChainParameters,
+    #[serde(default)]
+    pub pending_parameters: Option<ChainParameters>,
+    pub next_available_vote_id: u32,
+    pub active_committee_members: Vec<CommitteeMemberId>,
+    pub active_witnesses: Vec<WitnessId>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicGlobalProperties {
+    pub id: DynamicGlobalPropertyId,
+    pub head_block_number: u32,
+    pub head_block_id: String,
+    pub time: NaiveDateTime,
+    pub current_witness: WitnessId,
+    pub next_maintenance_time: NaiveDateTime,
+    #[serde(default)]
+    pub last_vote_tally_time: Option<NaiveDateTime>,
+    pub last_budget_time: NaiveDateTime,
+    #[serde(deserialize_with = "deserialize_share_amount")]
+    pub witness_budget: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub total_pob: i64,
+    #[serde(default, deserialize_with = "deserialize_share_amount")]
+    pub total_inactive: i64,
+    pub accounts_registered_this_interval: u32,
+    pub recently_missed_count: u32,
+    pub current_aslot: u64,
+    pub recent_slots_filled: String,
+    pub dynamic_flags: u32,
+    pub last_irreversible_block_num: u32
+}
+
+impl DynamicGlobalProperties {
+    pub fn get_ref_block_num(&self) -> u16 {
+        return (self.head_block_number & 0xffff) as u16;
+    }
+
+    pub fn get_ref_block_prefix(&self) -> Option<u32> {
+        let prefix = self.head_block_id.get(8..16)?;
+        let bytes = u32::from_str_radix(prefix, 16).ok()?;
+        return Some(bytes.swap_bytes());
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImmutableChainParameters {
+    pub min_committee_member_count: u16,
+    pub min_witness_count: u16,
+    pub num_special_accounts: u32,
+    pub num_special_assets: u32
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainProperties {
+    pub id: ChainPropertyId,
+    pub chain_id: String,
+    pub immutable_parameters: ImmutableChainParameters
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    #[serde(rename = "GRAPHENE_SYMBOL")]
+    pub symbol: String,
+    #[serde(rename = "GRAPHENE_ADDRESS_PREFIX")]
+    pub address_prefix: String,
+    #[serde(rename = "GRAPHENE_BLOCKCHAIN_PRECISION", deserialize_with = "deserialize_share_amount")]
+    pub blockchain_precision: i64,
+    #[serde(rename = "GRAPHENE_BLOCKCHAIN_PRECISION_DIGITS")]
+    pub blockchain_precision_digits: u8,
+    #[serde(rename = "GRAPHENE_MAX_SHARE_SUPPLY", deserialize_with = "deserialize_share_amount")]
+    pub max_share_supply: i64,
+    #[serde(rename = "GRAPHENE_MIN_BLOCK_INTERVAL")]
+    pub min_block_interval: u32,
+    #[serde(rename = "GRAPHENE_MAX_BLOCK_INTERVAL")]
+    pub max_block_interval: u32,
+    #[serde(rename = "GRAPHENE_MIN_ACCOUNT_NAME_LENGTH")]
+    pub min_account_name_length: u32,
+    #[serde(rename = "GRAPHENE_MAX_ACCOUNT_NAME_LENGTH")]
+    pub max_account_name_length: u32,
+    #[serde(rename = "GRAPHENE_MIN_ASSET_SYMBOL_LENGTH")]
+    pub min_asset_symbol_length: u32,
+    #[serde(rename = "GRAPHENE_MAX_ASSET_SYMBOL_LENGTH")]
+    pub max_asset_symbol_length: u32,
+    #[serde(flatten)]
+    pub other: Map<String, Value>
+}
+
+impl ChainConfig {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        return self.other.get(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chain_parameters(scale: u32) -> Value {
+        return json!({
+            "current_fees": {
+                "parameters": [
+                    [0, {"fee": 86869, "price_per_kbyte": 48260}],
+                    [1, {"fee": 482, "price_per_kbyte": "9652"}],
+                    [5, {"basic_fee": 2895624, "premium_fee": 96520798, "price_per_kbyte": 9652}]
+                ],
+                "scale": scale
+            },
+            "block_interval": 3,
+            "maintenance_interval": 3600,
+            "maintenance_skip_slots": 3,
+            "committee_proposal_review_period": 3600,
+            "maximum_transaction_size": 98304,
+            "maximum_block_size": 2000000,
+            "maximum_time_until_expiration": 86400,
+            "maximum_proposal_lifetime": 2419200,
+            "maximum_asset_whitelist_authorities": 10,
+            "maximum_asset_feed_publishers": 10,
+            "maximum_witness_count": 1001,
+            "maximum_committee_count": 1001,
+            "maximum_authority_membership": 10,
+            "reserve_percent_of_fee": 2000,
+            "network_percent_of_fee": 2000,
+            "lifetime_referrer_percent_of_fee": 3000,
+            "cashback_vesting_period_seconds": 7776000,
+            "cashback_vesting_threshold": 10000000,
+            "count_non_member_votes": true,
+            "allow_non_member_whitelists": false,
+            "witness_pay_per_block": 35000,
+            "witness_pay_vesting_seconds": 86400,
+            "worker_budget_per_day": "50000000000",
+            "max_predicate_opcode": 1,
+            "fee_liquidation_threshold": 10000000,
+            "accounts_per_fee_scale": 1000,
+            "account_fee_scale_bitshifts": 0,
+            "max_authority_depth": 2,
+            "extensions": {"updatable_htlc_options": {"max_timeout_secs": 2592000, "max_preimage_size": 1024000}}
+        });
+    }
+
+    #[test]
+    fn global_properties_fee_schedule() {
+        let properties: GlobalProperties = serde_json::from_value(json!({
+            "id": "2.0.0",
+            "parameters": chain_parameters(GRAPHENE_100_PERCENT),
+            "next_available_vote_id": 1143,
+            "active_committee_members": ["1.5.20", "1.5.22"],
+            "active_witnesses": ["1.6.16", "1.6.17"]
+        })).unwrap();
+        let fees = &properties.parameters.current_fees;
+
+        assert!(properties.pending_parameters.is_none());
+        assert_eq!(properties.active_witnesses[0], WitnessId::new(16));
+        assert_eq!(properties.parameters.worker_budget_per_day, 50000000000);
+        assert_eq!(fees.get_fee(0), Some(86869));
+        assert_eq!(fees.get_scaled_fee(1, "price_per_kbyte"), Some(9652));
+        assert_eq!(fees.get_scaled_fee(5, "premium_fee"), Some(96520798));
+        assert_eq!(fees.get_fee(5), None);
+        assert_eq!(fees.get_fee(2), None);
+    }
+
+    #[test]
+    fn fee_schedule_applies_scale() {
+        let parameters: ChainParameters = serde_json::from_value(chain_parameters(5000)).unwrap();
+        let fees = &parameters.current_fees;
+
+        assert_eq!(fees.get_fee(0), Some(43434));
+        assert_eq!(fees.get_scaled_fee(5, "basic_fee"), Some(1447812));
+        assert_eq!(fees.get_parameters(1).map(|parameters| parameters.get_field("fee")), Some(Some(482)));
+    }
+
+    #[test]
+    fn dynamic_global_properties_reference_block() {
+        let properties: DynamicGlobalProperties = serde_json::from_value(json!({
+            "id": "2.1.0",
+            "head_block_number": 62121954,
+            "head_block_id": "03b3e7e21df13b1a7a0fd9a1ff2dcb14b1ab26ce",
+            "time": "2021-11-20T10:15:42",
+            "current_witness": "1.6.69",
+            "next_maintenance_time": "2021-11-20T11:00:00",
+            "last_budget_time": "2021-11-20T10:00:00",
+            "witness_budget": "6480000000",
+            "total_pob": "1000000",
+            "total_inactive": 0,
+            "accounts_registered_this_interval": 4,
+            "recently_missed_count": 0,
+            "current_aslot": 62273512,
+            "recent_slots_filled": "340282366920938463463374607431768211455",
+            "dynamic_flags": 0,
+            "last_irreversible_block_num": 62121935
+        })).unwrap();
+
+        assert_eq!(properties.witness_budget, 6480000000);
+        assert_eq!(properties.total_pob, 1000000);
+        assert!(properties.last_vote_tally_time.is_none());
+        assert_eq!(properties.get_ref_block_num(), 0xe7e2);
+        assert_eq!(properties.get_ref_block_prefix(), Some(0x1a3bf11d));
+    }
+
+    #[test]
+    fn chain_config_keeps_unmapped_keys() {
+        let config: ChainConfig = serde_json::from_value(json!({
+            "GRAPHENE_SYMBOL": "BTS",
+            "GRAPHENE_ADDRESS_PREFIX": "BTS",
+            "GRAPHENE_BLOCKCHAIN_PRECISION": "100000",
+            "GRAPHENE_BLOCKCHAIN_PRECISION_DIGITS": 5,
+            "GRAPHENE_MAX_SHARE_SUPPLY": "1000000000000000",
+            "GRAPHENE_MIN_BLOCK_INTERVAL": 1,
+            "GRAPHENE_MAX_BLOCK_INTERVAL": 30,
+            "GRAPHENE_MIN_ACCOUNT_NAME_LENGTH": 1,
+            "GRAPHENE_MAX_ACCOUNT_NAME_LENGTH": 63,
+            "GRAPHENE_MIN_ASSET_SYMBOL_LENGTH": 3,
+            "GRAPHENE_MAX_ASSET_SYMBOL_LENGTH": 16,
+            "GRAPHENE_DEFAULT_BLOCK_INTERVAL": 5
+        })).unwrap();
+
+        assert_eq!(config.blockchain_precision, 100000);
+        assert_eq!(config.max_share_supply, 1000000000000000);
+        assert_eq!(config.get("GRAPHENE_DEFAULT_BLOCK_INTERVAL"), Some(&json!(5)));
+        assert_eq!(config.get("GRAPHENE_SYMBOL"), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,215 @@
+use std::collections::BTreeSet;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::object_id::{AccountId, ObjectId};
+use crate::models::operations::OperationPayload;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation(pub u8, pub Value);
+
+impl Operation {
+    pub fn get_type(&self) -> u8 {
+        return self.0;
+    }
+
+    pub fn get_payload(&self) -> &Value {
+        return &self.1;
+    }
+
+    pub fn decode(&self) -> OperationPayload {
+        return OperationPayload::from_operation(self.0, &self.1);
+    }
+
+    pub fn involved_accounts(&self) -> BTreeSet<AccountId> {
+        let mut accounts = BTreeSet::new();
+
+        match self.decode() {
+            OperationPayload::Transfer(transfer) => {
+                accounts.insert(transfer.from);
+                accounts.insert(transfer.to);
+            }
+            OperationPayload::LimitOrderCreate(order) => {
+                accounts.insert(order.seller);
+            }
+            OperationPayload::LimitOrderCancel(cancel) => {
+                accounts.insert(cancel.fee_paying_account);
+            }
+            OperationPayload::CallOrderUpdate(update) => {
+                accounts.insert(update.funding_account);
+            }
+            OperationPayload::FillOrder(fill) => {
+                accounts.insert(fill.account_id);
+            }
+            OperationPayload::Invalid(_, payload, _) | OperationPayload::Other(_, payload) => {
+                collect_account_ids(&payload, &mut accounts)
+            }
+        }
+
+        return accounts;
+    }
+}
+
+fn collect_account_ids(value: &Value, accounts: &mut BTreeSet<AccountId>) {
+    match value {
+        Value::String(text) => {
+            if let Ok(account) = text.parse::<AccountId>() {
+                accounts.insert(account);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_account_ids(value, accounts);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values() {
+                collect_account_ids(value, accounts);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub ref_block_num: u16,
+    pub ref_block_prefix: u32,
+    pub expiration: NaiveDateTime,
+    pub operations: Vec<Operation>,
+    #[serde(default)]
+    pub extensions: Vec<Value>,
+    #[serde(default)]
+    pub signatures: Vec<String>
+}
+
+impl SignedTransaction {
+    pub fn involved_accounts(&self) -> BTreeSet<AccountId> {
+        return self.operations.iter()
+            .flat_map(|operation| operation.involved_accounts())
+            .collect();
+    }
+
+    pub fn involves_any(&self, accounts: &[AccountId]) -> bool {
+        let involved = self.involved_accounts();
+        return accounts.iter().any(|account| involved.contains(account));
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationResult(pub u8, pub Value);
+
+impl OperationResult {
+    pub fn get_type(&self) -> u8 {
+        return self.0;
+    }
+
+    pub fn get_payload(&self) -> &Value {
+        return &self.1;
+    }
+
+    pub fn get_object_id(&self) -> Option<ObjectId> {
+        return self.1.as_str().and_then(|id| id.parse().ok());
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessedTransaction {
+    #[serde(flatten)]
+    pub transaction: SignedTransaction,
+    #[serde(default)]
+    pub operation_results: Vec<OperationResult>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn accounts(ids: &[u64]) -> BTreeSet<AccountId> {
+        return ids.iter().map(|id| AccountId::new(*id)).collect();
+    }
+
+    #[test]
+    fn transfer_accounts_ignore_memo_and_extensions() {
+        let operation = Operation(0, json!({
+            "fee": {"amount": 100, "asset_id": "1.3.0"},
+            "from": "1.2.7",
+            "to": "1.2.8",
+            "amount": {"amount": 5000, "asset_id": "1.3.0"},
+            "memo": {"from": "1.2.99", "to": "1.2.98", "nonce": 1, "message": ""},
+            "extensions": ["1.2.97"]
+        }));
+
+        assert_eq!(operation.involved_accounts(), accounts(&[7, 8]));
+    }
+
+    #[test]
+    fn typed_operations_use_their_account_fields() {
+        let create = Operation(1, json!({
+            "fee": {"amount": 100, "asset_id": "1.3.0"},
+            "seller": "1.2.7",
+            "amount_to_sell": {"amount": 10, "asset_id": "1.3.0"},
+            "min_to_receive": {"amount": 20, "asset_id": "1.3.1"},
+            "expiration": "2030-01-01T00:00:00",
+            "fill_or_kill": false,
+            "extensions": []
+        }));
+        let cancel = Operation(2, json!({
+            "fee": {"amount": 100, "asset_id": "1.3.0"},
+            "fee_paying_account": "1.2.8",
+            "order": "1.7.5",
+            "extensions": []
+        }));
+        let update = Operation(3, json!({
+            "fee": {"amount": 100, "asset_id": "1.3.0"},
+            "funding_account": "1.2.9",
+            "delta_collateral": {"amount": 10, "asset_id": "1.3.0"},
+            "delta_debt": {"amount": 20, "asset_id": "1.3.1"},
+            "extensions": []
+        }));
+        let fill = Operation(4, json!({
+            "fee": {"amount": 0, "asset_id": "1.3.0"},
+            "order_id": "1.7.5",
+            "account_id": "1.2.10",
+            "pays": {"amount": 10, "asset_id": "1.3.0"},
+            "receives": {"amount": 20, "asset_id": "1.3.1"}
+        }));
+
+        assert_eq!(create.involved_accounts(), accounts(&[7]));
+        assert_eq!(cancel.involved_accounts(), accounts(&[8]));
+        assert_eq!(update.involved_accounts(), accounts(&[9]));
+        assert_eq!(fill.involved_accounts(), accounts(&[10]));
+    }
+
+    #[test]
+    fn unknown_operations_fall_back_to_scanning() {
+        let operation = Operation(5, json!({
+            "fee": {"amount": 100, "asset_id": "1.3.0"},
+            "registrar": "1.2.7",
+            "owner": {"account_auths": [["1.2.8", 1]]}
+        }));
+
+        assert_eq!(operation.involved_accounts(), accounts(&[7, 8]));
+    }
+
+    #[test]
+    fn operation_results_expose_created_object_ids() {
+        let results: Vec<OperationResult> = serde_json::from_value(json!([
+            [0, {}],
+            [1, "1.7.123"],
+            [1, "not an id"],
+            [2, {"amount": 100000, "asset_id": "1.3.0"}],
+            [5, {"new_objects": ["1.7.124"], "impacted_accounts": ["1.2.7"]}]
+        ])).unwrap();
+
+        assert_eq!(results[0].get_type(), 0);
+        assert_eq!(results[0].get_object_id(), None);
+        assert_eq!(results[1].get_object_id(), Some("1.7.123".parse().unwrap()));
+        assert_eq!(results[2].get_object_id(), None);
+        assert_eq!(results[3].get_object_id(), None);
+        assert_eq!(results[4].get_type(), 5);
+        assert_eq!(results[4].get_payload()["new_objects"][0], json!("1.7.124"));
+    }
+}
\ No newline at end of file
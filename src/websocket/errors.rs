@@ -16,8 +16,8 @@ pub enum WebSocketError {
     NotConnected,
     ErrorSenderChannel,
     ErrorReceiverChannel,
-    SubscribingError
-    
+    SubscribingError,
+    SubscriptionLagged
 }
 
 impl fmt::Display for WebSocketError {
@@ -31,6 +31,7 @@ impl fmt::Display for WebSocketError {
             WebSocketError::ErrorSenderChannel => write!(f,"Error receive message on Sender Task"),
             WebSocketError::ErrorReceiverChannel => write!(f,"Error sending message on Receiver Task"),
             WebSocketError::SubscribingError => write!(f,"Error on subscribing"),
+            WebSocketError::SubscriptionLagged => write!(f,"Subscription buffer is full, notice has been dropped"),
         }
     }
 }
@@ -46,6 +47,7 @@ impl Error for WebSocketError {
             WebSocketError::ErrorSenderChannel => "Error receive message on Sender Task",
             WebSocketError::ErrorReceiverChannel => "Error sending message on Receiver Task",
             WebSocketError::SubscribingError => "Error on subscribing",
+            WebSocketError::SubscriptionLagged => "Subscription buffer is full, notice has been dropped",
         }
     }
 }
@@ -63,6 +65,7 @@ impl FromStr for WebSocketError {
             "\"WebSocketError::ErrorSenderChannel\"" => Ok(WebSocketError::ErrorSenderChannel),
             "\"WebSocketError::ErrorReceiverChannel\"" => Ok(WebSocketError::ErrorReceiverChannel),
             "\"WebSocketError::SubscribingError\"" => Ok(WebSocketError::SubscribingError),
+            "\"WebSocketError::SubscriptionLagged\"" => Ok(WebSocketError::SubscriptionLagged),
             _ => Err(())
         }
     }
@@ -79,6 +82,7 @@ impl From<WebSocketError> for Value {
             WebSocketError::ErrorSenderChannel => json!("WebSocketError::ErrorSenderChannel"),
             WebSocketError::ErrorReceiverChannel => json!("WebSocketError::ErrorReceiverChannel"),
             WebSocketError::SubscribingError => json!("WebSocketError::SubscribingError"),
+            WebSocketError::SubscriptionLagged => json!("WebSocketError::SubscriptionLagged"),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,77 @@
+use fastwebsockets::{handshake, FragmentCollectorRead, WebSocketWrite};
+use hyper::upgrade::Upgraded;
+use hyper::{header, Body, Request};
+use std::error::Error;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::io::{ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls;
+use tokio_rustls::TlsConnector;
+
+pub type SocketReader = FragmentCollectorRead<ReadHalf<Upgraded>>;
+
+pub type SocketWriter = WebSocketWrite<WriteHalf<Upgraded>>;
+
+struct SpawnExecutor;
+
+impl<Fut> hyper::rt::Executor<Fut> for SpawnExecutor
+where
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static
+{
+    fn execute(&self, fut: Fut) {
+        tokio::task::spawn(fut);
+    }
+}
+
+fn tls_connector() -> TlsConnector {
+    let mut root_store = rustls::RootCertStore::empty();
+
+    root_store.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|trust_anchor| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+            trust_anchor.subject,
+            trust_anchor.spki,
+            trust_anchor.name_constraints
+        )
+    }));
+
+    let config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+
+    return TlsConnector::from(Arc::new(config));
+}
+
+pub async fn connect(url: &str) -> Result<(SocketReader, SocketWriter), Box<dyn Error + Send + Sync>> {
+    let url = url::Url::parse(url)?;
+    let host = url.host_str().ok_or("invalid host")?;
+    let port = url.port_or_known_default().ok_or("unknown port")?;
+    let address = format!("{}:{}", host, port);
+
+    let tcp_stream = TcpStream::connect(&address).await?;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(url.to_string())
+        .header("Host", &address)
+        .header(header::UPGRADE, "websocket")
+        .header(header::CONNECTION, "upgrade")
+        .header("Sec-WebSocket-Key", handshake::generate_key())
+        .header("Sec-WebSocket-Version", "13")
+        .body(Body::empty())?;
+
+    let (ws, _response) = match url.scheme() {
+        "wss" | "https" => {
+            let server_name = rustls::ServerName::try_from(host)?;
+            let tls_stream = tls_connector().connect(server_name, tcp_stream).await?;
+            handshake::client(&SpawnExecutor, request, tls_stream).await?
+        }
+        _ => handshake::client(&SpawnExecutor, request, tcp_stream).await?
+    };
+
+    let (reader, writer) = ws.split(tokio::io::split);
+
+    return Ok((FragmentCollectorRead::new(reader), writer));
+}
\ No newline at end of file
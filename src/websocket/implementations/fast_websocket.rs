@@ -1,17 +1,23 @@
-use fast_websocket_client::{client, connect};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::task;
-use tokio::sync::Mutex;
-use tokio::sync::mpsc::{Sender, Receiver};
-use serde_json::Value;
+use tokio::sync::{oneshot, Mutex, Notify};
+use tokio::sync::mpsc::{channel, Sender, Receiver};
+use serde_json::{json, Value};
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
+use crate::config::config::ClientConfig;
+use crate::websocket::implementations::timeouts::Timeout;
 
 use crate::websocket::{
-    interface::IWebSocket, 
+    interface::{IWebSocket, ConnectionState, SessionEvent}, 
     errors::WebSocketError,
     implementations::{
-        message_channel::{MessageChannel, WebsocketCloseRequest},
+        connection::connect,
+        message_channel::{MessageChannel, WriteRequest},
+        pending_requests::PendingRequests,
+        session::Session,
         listeners::{
             spawn_sender_task, 
             spawn_receiver_task
@@ -20,83 +26,145 @@ use crate::websocket::{
 };
 
 pub struct FastWebsocketClient {
-    socket: Option<Arc<Mutex<client::Online>>>,
-    sender_channel: Option<MessageChannel<Value>>,
-    receiver_channel: Option<MessageChannel<Value>>,
+    shutdown: Mutex<Option<Arc<Notify>>>,
+    sender_channel: Mutex<Option<Sender<WriteRequest>>>,
+    receiver_channel: Mutex<Option<MessageChannel<Value>>>,
     callback_channel: Option<Sender<(u64, Value)>>,
+    pending_requests: Arc<PendingRequests>,
+    session: Arc<Session>,
+    closing: Arc<AtomicBool>,
+    state_channel: Option<Sender<ConnectionState>>,
+    session_channel: Option<Sender<SessionEvent>>,
+    config: Arc<ClientConfig>,
     url: String
 }
 
 impl FastWebsocketClient {
     pub fn new(url: String) -> Self {
+        return FastWebsocketClient::with_config(url, Arc::new(ClientConfig::default()));
+    }
+
+    pub fn with_config(url: String, config: Arc<ClientConfig>) -> Self {
         Self {
-            socket: None,
-            sender_channel: None,
-            receiver_channel: None,
+            shutdown: Mutex::new(None),
+            sender_channel: Mutex::new(None),
+            receiver_channel: Mutex::new(None),
             callback_channel: None,
-            url: url
+            pending_requests: Arc::new(PendingRequests::new()),
+            session: Arc::new(Session::new()),
+            closing: Arc::new(AtomicBool::new(false)),
+            state_channel: None,
+            session_channel: None,
+            config,
+            url
         }
     }
 
-    fn create_sender_channel(&mut self) -> (Sender<Value>,Receiver<Value>) {
+    async fn create_sender_channel(&self) -> (Sender<WriteRequest>,Receiver<WriteRequest>) {
 
-        let mut channel = MessageChannel::<Value>::new();
-        let (tx,rx) = channel.create_channel();
-        self.sender_channel = Some(channel);
+        let (tx,rx) = channel::<WriteRequest>(100);
+        *self.sender_channel.lock().await = Some(tx.clone());
         return (tx, rx);
     }
 
-    fn create_receiver_channel(&mut self) -> (Sender<Value>,Receiver<Value>) {
+    async fn create_receiver_channel(&self) -> Sender<Value> {
 
         let mut channel = MessageChannel::<Value>::new();
-        let (tx,rx) = channel.create_channel();
-        self.receiver_channel = Some(channel);
-        return (tx, rx);
+        let tx = channel.create_channel();
+        *self.receiver_channel.lock().await = Some(channel);
+        return tx;
     }
 
     pub fn set_callback_channel(&mut self, channel: Sender<(u64, Value)>) {
         self.callback_channel = Some(channel);
     }
 
+    pub fn set_connection_state_channel(&mut self, channel: Sender<ConnectionState>) {
+        self.state_channel = Some(channel);
+    }
+
+    pub fn set_session_channel(&mut self, channel: Sender<SessionEvent>) {
+        self.session_channel = Some(channel);
+    }
+
+    fn notify_state(&self, state: ConnectionState) {
+        if let Some(channel) = &self.state_channel {
+            let _ = channel.try_send(state);
+        }
+    }
+
 }
 
 impl IWebSocket for FastWebsocketClient {
 
-    fn connect(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    fn connect(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
         let future = async {
-            match connect(&self.url).await {
-                Ok(ws) => {
+            let connection = connect(&self.url).await.map_err(|e| e.to_string());
 
-                    let master_socket = Arc::new(Mutex::new(ws));
+            match connection {
+                Ok((reader, writer)) => {
 
-                    let clone_socket = Arc::clone(&master_socket);
-    
-                    let (tx,rx) = self.create_sender_channel();
+                    self.closing.store(false, Ordering::SeqCst);
+
+                    let shutdown = Arc::new(Notify::new());
+
+                    let sender_config = Arc::clone(&self.config);
+
+                    let (writer_tx, writer_rx) = self.create_sender_channel().await;
 
                     task::spawn(async move {
 
-                        spawn_sender_task(clone_socket, rx, tx).await;
+                        spawn_sender_task(writer, writer_rx, sender_config).await;
     
                     });
 
-                    let clone_socket = Arc::clone(&master_socket);
+                    let tx = self.create_receiver_channel().await;
+
+                    let tx_cb_call = self.callback_channel.clone();
+
+                    let pending_requests = Arc::clone(&self.pending_requests);
+
+                    let session = Arc::clone(&self.session);
 
-                    let (tx,rx) = self.create_receiver_channel();
+                    let closing = Arc::clone(&self.closing);
 
-                    let tx_cb_call = self.callback_channel.take();
+                    let state_channel = self.state_channel.clone();
+
+                    let session_channel = self.session_channel.clone();
+
+                    let url = self.url.clone();
+
+                    let receiver_shutdown = Arc::clone(&shutdown);
+
+                    let config = Arc::clone(&self.config);
     
                     task::spawn(async move {
     
-                        spawn_receiver_task(clone_socket, rx, tx, tx_cb_call).await;
+                        spawn_receiver_task(
+                            reader,
+                            writer_tx,
+                            tx,
+                            tx_cb_call,
+                            pending_requests,
+                            url,
+                            session,
+                            closing,
+                            state_channel,
+                            session_channel,
+                            receiver_shutdown,
+                            config
+                        ).await;
     
                     });
     
-                    self.socket = Some(master_socket);
+                    *self.shutdown.lock().await = Some(shutdown);
+
+                    self.notify_state(ConnectionState::Connected);
 
                 }
 
                 Err(e) => {
-                    println!("Not Connected: {:?}", e);
+                    println!("Not Connected: {}", e);
                     return Err(WebSocketError::ConnectionError);
                 }
             }
@@ -106,19 +174,18 @@ impl IWebSocket for FastWebsocketClient {
         return Box::pin(future);
     }
 
-    fn send(&mut self, msg: Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    fn send(&self, msg: Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
         let future = async {
-            if let Some(channel) = &mut self.sender_channel {
-                if let Ok(_sent) = channel.send(msg).await {
-                    if let Ok(Some(msg)) = channel.recv().await {
-                        let _deserialized_error: WebSocketError = match msg.to_string().parse() {
-                            Ok(error) => return Err(error),
-                            Err(_msg) => {
-                                return Ok(());
-                            }
-                        };
-                    } else {
-                        return Err(WebSocketError::ErrorSenderChannel);
+            let sender = self.sender_channel.lock().await.clone();
+
+            if let Some(sender) = sender {
+                let (ack, sent) = oneshot::channel();
+                if let Ok(_queued) = sender.send(WriteRequest::Message(msg, ack)).await {
+                    let wait = self.config.get_timeout(Timeout::WebSocketSend) + self.config.get_timeout(Timeout::WebSocketReceive);
+                    match tokio::time::timeout(Duration::from_millis(wait), sent).await {
+                        Ok(Ok(result)) => return result,
+                        Ok(Err(_dropped)) => return Err(WebSocketError::ErrorSenderChannel),
+                        Err(_elapsed) => return Err(WebSocketError::MessageSendError)
                     }
                 } else {
                     return Err(WebSocketError::MessageSendError);
@@ -131,9 +198,9 @@ impl IWebSocket for FastWebsocketClient {
         return Box::pin(future);
     }
 
-    fn receive(&mut self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + '_>> {
+    fn receive(&self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
         let future = async {
-            if let Some(channel) = &mut self.receiver_channel {
+            if let Some(channel) = self.receiver_channel.lock().await.as_mut() {
                 if let Ok(Some(msg)) = channel.recv().await {
                     let _deserialized_error: WebSocketError = match msg.to_string().parse() {
                         Ok(error) => return Err(error),
@@ -150,40 +217,206 @@ impl IWebSocket for FastWebsocketClient {
         return Box::pin(future);
     }
 
-    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    fn call(&self, mut msg: Value) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+        let future = async move {
+            let (request_id, response) = self.pending_requests.register();
 
-        let future = async {
+            msg["id"] = json!(request_id);
 
-            if let Some(channel) = &mut self.sender_channel {
-                if let Ok(_sent) = channel.send(WebsocketCloseRequest::Close.into()).await {
+            if let Err(error) = self.send(msg).await {
+                self.pending_requests.cancel(request_id);
+                return Err(error);
+            }
 
-                } else {
-                    return Err(WebSocketError::MessageSendError);
+            match tokio::time::timeout(
+                Duration::from_millis(self.config.get_timeout(Timeout::WebSocketReceive)),
+                response
+            ).await {
+                Ok(Ok(result)) => return Ok(result),
+                Ok(Err(_dropped)) => return Err(WebSocketError::NotConnected),
+                Err(_elapsed) => {
+                    self.pending_requests.cancel(request_id);
+                    return Err(WebSocketError::MessageReceiveError);
                 }
-            } else {
-                return Err(WebSocketError::MessageSendError);
             }
+        };
 
-            let socket_clone:Arc<Mutex<client::Online>>;
+        return Box::pin(future);
+    }
 
-            if let Some(socket) = &mut self.socket {
-                socket_clone = Arc::clone(&socket);
+    fn add_session_request(&self, key: String, request: Value, result: Option<Value>) {
+        self.session.add(key, request, result);
+    }
+
+    fn remove_session_request(&self, key: &str) {
+        self.session.remove(key);
+    }
+
+    fn close(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+
+        let future = async {
+
+            self.closing.store(true, Ordering::SeqCst);
+
+            let sender = self.sender_channel.lock().await.take();
+
+            let shutdown = self.shutdown.lock().await.take();
+
+            let sender = if let Some(sender) = sender {
+                sender
             } else {
                 return Err(WebSocketError::NotConnected);
+            };
+
+            let (ack, sent) = oneshot::channel();
+
+            if sender.send(WriteRequest::Close(ack)).await.is_err() {
+                return Err(WebSocketError::MessageSendError);
             }
-    
-            let _x = match socket_clone.lock().await.send_close(&[]).await {
-                Ok(_sucess) => {
-                    self.socket = None;
-                    return Ok(());
-                }
-                Err(_err) => {
-                    return Err(WebSocketError::WebSocketNotClosed);
-                }
+
+            let result = match tokio::time::timeout(
+                Duration::from_millis(self.config.get_timeout(Timeout::WebsocketClose)),
+                sent
+            ).await {
+                Ok(Ok(result)) => result,
+                _ => Err(WebSocketError::WebSocketNotClosed)
             };
+
+            if let Some(shutdown) = shutdown {
+                shutdown.notify_one();
+            }
+
+            self.pending_requests.clear();
+
+            if result.is_ok() {
+                self.notify_state(ConnectionState::Closed);
+            }
+
+            return result;
         };
 
         return Box::pin(future);
     }
 
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::Mutex as StdMutex;
+    use tungstenite::{accept, Message};
+
+    fn method_of(request: &Value) -> String {
+        return request.pointer("/params/1").and_then(|method| method.as_str()).unwrap_or("").to_string();
+    }
+
+    fn node_call(method: &str) -> Value {
+        return json!({"method": "call", "params": [1, method, []]});
+    }
+
+    async fn wait_for_state(rx_state: &mut Receiver<ConnectionState>, expected: fn(&ConnectionState) -> bool) -> ConnectionState {
+        loop {
+            let state = tokio::time::timeout(Duration::from_secs(5), rx_state.recv()).await.unwrap().unwrap();
+            if expected(&state) {
+                return state;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn calls_during_reconnect_wait_for_session_replay() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let replayed: Arc<StdMutex<Vec<String>>> = Arc::new(StdMutex::new(Vec::new()));
+        let node_replayed = Arc::clone(&replayed);
+
+        std::thread::spawn(move || {
+            for (connection, stream) in listener.incoming().flatten().enumerate() {
+                let replayed = Arc::clone(&node_replayed);
+
+                std::thread::spawn(move || {
+                    let mut ws = if let Ok(ws) = accept(stream) { ws } else { return; };
+
+                    while let Ok(Message::Text(text)) = ws.read_message() {
+                        let request: Value = serde_json::from_str(&text).unwrap();
+                        let method = method_of(&request);
+
+                        if connection > 0 {
+                            if method == "login" {
+                                std::thread::sleep(std::time::Duration::from_millis(300));
+                            }
+                            replayed.lock().unwrap().push(method.clone());
+                        }
+
+                        let response = json!({"id": request["id"], "result": true});
+                        if ws.write_message(Message::Text(response.to_string())).is_err() {
+                            return;
+                        }
+
+                        if connection == 0 && method == "login" {
+                            let _ = ws.close(None);
+                            let _ = ws.write_pending();
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        let config = ClientConfig::builder()
+            .timeout(Timeout::ReconnectMin, 50)
+            .timeout(Timeout::WebSocketReceive, 2000)
+            .build()
+            .unwrap();
+
+        let (tx_state, mut rx_state) = channel::<ConnectionState>(20);
+        let mut client = FastWebsocketClient::with_config(url, Arc::new(config));
+        client.set_connection_state_channel(tx_state);
+
+        client.connect().await.unwrap();
+
+        let login = json!({"method": "call", "params": [1, "login", ["", ""]]});
+        client.add_session_request(String::from("login"), login.clone(), Some(json!(true)));
+        client.call(login).await.unwrap();
+
+        wait_for_state(&mut rx_state, |state| *state == ConnectionState::Disconnected).await;
+
+        let result = client.call(node_call("get_chain_id")).await.unwrap();
+
+        assert_eq!(result["result"], json!(true));
+        assert_eq!(*replayed.lock().unwrap(), vec![String::from("login"), String::from("get_chain_id")]);
+    }
+
+    #[tokio::test]
+    async fn reconnect_gives_up_after_max_attempts() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            let stream = listener.incoming().flatten().next().unwrap();
+            drop(listener);
+            let mut ws = accept(stream).unwrap();
+            let _ = ws.close(None);
+            let _ = ws.write_pending();
+        });
+
+        let config = ClientConfig::builder()
+            .timeout(Timeout::ReconnectMin, 10)
+            .timeout(Timeout::ReconnectMax, 20)
+            .reconnect_max_attempts(2)
+            .build()
+            .unwrap();
+
+        let (tx_state, mut rx_state) = channel::<ConnectionState>(20);
+        let mut client = FastWebsocketClient::with_config(url, Arc::new(config));
+        client.set_connection_state_channel(tx_state);
+
+        client.connect().await.unwrap();
+
+        let state = wait_for_state(&mut rx_state, |state| matches!(state, ConnectionState::ReconnectFailed(_))).await;
+
+        assert_eq!(state, ConnectionState::ReconnectFailed(2));
+        assert!(client.call(node_call("get_chain_id")).await.is_err());
+    }
 }
\ No newline at end of file
@@ -1,68 +1,94 @@
-use fast_websocket_client::{client, OpCode};
+use fastwebsockets::{Frame, OpCode, Payload};
 use serde::de::Error;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio::sync::mpsc::{Sender, Receiver};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use tokio::sync::{oneshot, Notify};
+use tokio::task::JoinHandle;
+use tokio::sync::mpsc::{unbounded_channel, Sender, Receiver, UnboundedSender, UnboundedReceiver};
 use serde_json::{json, Value};
 use std::time::Duration;
-use crate::config::config::CONFIG;
+use crate::config::config::ClientConfig;
 use crate::websocket::implementations::timeouts::Timeout;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use crate::websocket::{
     errors::WebSocketError,
-    implementations::message_channel::WebsocketCloseRequest
+    implementations::{
+        connection::{connect, SocketReader, SocketWriter},
+        message_channel::{WebsocketCloseRequest, WriteRequest},
+        pending_requests::PendingRequests,
+        session::Session
+    }
 };
 
-use crate::websocket::interface::SubscriptionCallback;
+use crate::websocket::interface::{SubscriptionCallback, ConnectionState, SessionEvent};
 
 use crate::commands::subscriptions::responses::ChainResponse;
 
+async fn spawn_callback_worker(
+    cb_id: u64,
+    cb: Box<SubscriptionCallback>,
+    mut rx_notices: UnboundedReceiver<Value>,
+    error_channel: Option<Sender<(u64, WebSocketError)>>
+) {
+
+    while let Some(cb_msg) = rx_notices.recv().await {
+        if let Err(error) = cb(Some(cb_msg)).await {
+            println!("Callback {} failed: {}", cb_id, error);
+            if let Some(channel) = &error_channel {
+                let _ = channel.send((cb_id, error)).await;
+            }
+        }
+    }
+}
+
 pub async fn spawn_callbacks_table(
     mut rx_cb_register: Receiver<(u64, Box<SubscriptionCallback>)>,
     mut rx_cb_unregister: Receiver<u64>,
     mut rx_cb_call: Receiver<(u64, Value)>, 
-    mut rx_ws_close: Receiver<WebsocketCloseRequest>
+    mut rx_ws_close: Receiver<WebsocketCloseRequest>,
+    error_channel: Option<Sender<(u64, WebSocketError)>>
 ) {
 
     println!("Running Callback Table Task...");
 
-    let mut callbacks:HashMap<u64, Box<SubscriptionCallback>> = HashMap::new();
+    let mut callbacks:HashMap<u64, UnboundedSender<Value>> = HashMap::new();
 
     loop {
 
-        if let Ok(Some(_request)) = tokio::time::timeout(
-            Duration::from_millis(CONFIG.get_timeout(Timeout::WebsocketClose)), 
-            rx_ws_close.recv()
-        ).await {
-            println!("WebSocket close has been requested.");
-            break;
-        }
+        tokio::select! {
+            biased;
 
-        if let Ok(Some(cb)) = tokio::time::timeout(
-            Duration::from_millis(CONFIG.get_timeout(Timeout::WebsocketClose)), 
-            rx_cb_register.recv()
-        ).await {
-            println!("Registering Callback");
-            callbacks.insert(cb.0, cb.1);
-        }
+            _request = rx_ws_close.recv() => {
+                println!("WebSocket close has been requested.");
+                break;
+            }
 
-        if let Ok(Some(cb_id)) = tokio::time::timeout(
-            Duration::from_millis(CONFIG.get_timeout(Timeout::WebsocketClose)), 
-            rx_cb_unregister.recv()
-        ).await {
-            let _cb = callbacks.remove(&cb_id);
-        }
+            Some((cb_id, cb)) = rx_cb_register.recv() => {
+                println!("Registering Callback");
+                let (tx_notices, rx_notices) = unbounded_channel::<Value>();
+                tokio::task::spawn(spawn_callback_worker(cb_id, cb, rx_notices, error_channel.clone()));
+                callbacks.insert(cb_id, tx_notices);
+            }
+
+            Some(cb_id) = rx_cb_unregister.recv() => {
+                println!("Unregistering Callback");
+                let _cb = callbacks.remove(&cb_id);
+            }
+
+            Some((cb_id, cb_msg)) = rx_cb_call.recv() => {
+                println!("Calling Callback");
+                println!("cb_id: {}", cb_id);
+                println!("cb_msg: {}", cb_msg);
+                if let Some(cb) = callbacks.get(&cb_id) {
+                    let _ = cb.send(cb_msg);
+                } else {
+                    println!("No callback registered for cb_id: {}", cb_id);
+                }
+            }
 
-        if let Ok(Some(exec)) = tokio::time::timeout(
-            Duration::from_millis(CONFIG.get_timeout(Timeout::WebsocketClose)), 
-            rx_cb_call.recv()
-        ).await {
-            println!("Calling Callback");
-            println!("cb_id: {}", exec.0);
-            println!("cb_msg: {}", exec.1);
-            if let Some(cb) = callbacks.get(&(exec.0)) {
-                let _result = cb(Some(exec.1)).await;
+            else => {
+                break;
             }
         }
 
@@ -71,90 +97,362 @@ pub async fn spawn_callbacks_table(
     println!("Closing Callback Table...");
 }
 
+async fn write_message(writer: &mut SocketWriter, send_msg: Value, ack: oneshot::Sender<Result<(), WebSocketError>>, config: &ClientConfig) {
+    let result_str = send_msg.to_string();
+    println!("{}", result_str);
+
+    let frame = Frame::text(Payload::Owned(result_str.into_bytes()));
+
+    if let Ok(Ok(_sent)) = tokio::time::timeout(
+        Duration::from_millis(config.get_timeout(Timeout::WebSocketSend)),
+        writer.write_frame(frame)
+    ).await {
+        let _ = ack.send(Ok(()));
+    } else {
+        let _ = ack.send(Err(WebSocketError::MessageSendError));
+    }
+}
+
 pub async fn spawn_sender_task(
-    socket: Arc<Mutex<client::Online>>,
-    mut rx: Receiver<Value>,
-    tx: Sender<Value>
+    mut writer: SocketWriter,
+    mut rx: Receiver<WriteRequest>,
+    config: Arc<ClientConfig>
 ) {
 
-    loop {
-        if let Some(send_msg) = rx.recv().await {
-            let result_str: &str = &send_msg.to_string();
-            println!("{}", result_str);
+    let mut suspended = false;
 
-            match result_str.parse::<WebsocketCloseRequest>() {
-                Ok(_close) => {
-                    println!("WebSocket close has been requested.");
-                    break;
+    let mut queued: VecDeque<(Value, oneshot::Sender<Result<(), WebSocketError>>)> = VecDeque::new();
+
+    while let Some(request) = rx.recv().await {
+        match request {
+            WriteRequest::Message(send_msg, ack) => {
+                if suspended {
+                    queued.push_back((send_msg, ack));
+                } else {
+                    write_message(&mut writer, send_msg, ack, &config).await;
+                }
+            }
+            WriteRequest::Replay(send_msg, ack) => {
+                write_message(&mut writer, send_msg, ack, &config).await;
+            }
+            WriteRequest::Frame(frame) => {
+                if let Err(e) = writer.write_frame(frame).await {
+                    println!("Error writing control frame: {}", e);
+                }
+            }
+            WriteRequest::Replace(new_writer) => {
+                writer = new_writer;
+            }
+            WriteRequest::Suspend => {
+                suspended = true;
+            }
+            WriteRequest::Resume => {
+                suspended = false;
+                while let Some((send_msg, ack)) = queued.pop_front() {
+                    if !ack.is_closed() {
+                        write_message(&mut writer, send_msg, ack, &config).await;
+                    }
+                }
+            }
+            WriteRequest::Abort => {
+                println!("Reconnect gave up, failing queued requests.");
+                break;
+            }
+            WriteRequest::Close(ack) => {
+                println!("WebSocket close has been requested.");
+                if let Ok(_sent) = writer.write_frame(Frame::close(1000, &[])).await {
+                    let _ = ack.send(Ok(()));
+                } else {
+                    let _ = ack.send(Err(WebSocketError::WebSocketNotClosed));
                 }
-                Err(_e) => {}
+                break;
             }
+        }
+    }
+
+    for (_send_msg, ack) in queued {
+        let _ = ack.send(Err(WebSocketError::NotConnected));
+    }
+
+    println!("Closing Sender Task...");
+}
+
+fn notify_state(state_channel: &Option<Sender<ConnectionState>>, state: ConnectionState) {
+    if let Some(channel) = state_channel {
+        let _ = channel.try_send(state);
+    }
+}
+
+fn notify_session(session_channel: &Option<Sender<SessionEvent>>, event: SessionEvent) {
+    if let Some(channel) = session_channel {
+        let _ = channel.try_send(event);
+    }
+}
+
+pub async fn spawn_heartbeat_task(
+    writer: Sender<WriteRequest>,
+    missed: Arc<AtomicU32>,
+    heartbeat_lost: Arc<Notify>,
+    config: Arc<ClientConfig>
+) {
+
+    let interval = config.get_timeout(Timeout::Heartbeat);
+
+    if interval == 0 {
+        return;
+    }
+
+    let max_missed = config.get_heartbeat_max_missed();
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(interval)).await;
+
+        if missed.fetch_add(1, Ordering::SeqCst) >= max_missed {
+            println!("Heartbeat lost after {} missed intervals", max_missed);
+            heartbeat_lost.notify_one();
+            return;
+        }
+
+        let ping = Frame::new(true, OpCode::Ping, None, Payload::Owned(Vec::new()));
+
+        if writer.send(WriteRequest::Frame(ping)).await.is_err() {
+            return;
+        }
+    }
+}
+
+fn start_heartbeat(writer: &Sender<WriteRequest>, config: &Arc<ClientConfig>) -> (JoinHandle<()>, Arc<AtomicU32>, Arc<Notify>) {
+    let missed = Arc::new(AtomicU32::new(0));
+    let heartbeat_lost = Arc::new(Notify::new());
+
+    let heartbeat = tokio::task::spawn(spawn_heartbeat_task(
+        writer.clone(),
+        Arc::clone(&missed),
+        Arc::clone(&heartbeat_lost),
+        Arc::clone(config)
+    ));
+
+    return (heartbeat, missed, heartbeat_lost);
+}
+
+async fn replay_session(
+    writer: &Sender<WriteRequest>,
+    pending_requests: &Arc<PendingRequests>,
+    session: &Arc<Session>,
+    session_channel: &Option<Sender<SessionEvent>>,
+    config: &ClientConfig
+) {
+
+    for session_request in session.requests() {
+
+        let (request_id, response) = pending_requests.register();
+
+        let mut request = session_request.request.clone();
+
+        request["id"] = json!(request_id);
+
+        let (ack, sent) = oneshot::channel();
+
+        if writer.send(WriteRequest::Replay(request, ack)).await.is_err() {
+            pending_requests.cancel(request_id);
+            notify_session(session_channel, SessionEvent::Failed(session_request.key, WebSocketError::ErrorSenderChannel.into()));
+            return;
+        }
+
+        if let Ok(Ok(_sent)) = sent.await {
 
-            if let Ok(_result) =
-                tokio::time::timeout(
-                    Duration::from_millis(CONFIG.get_timeout(Timeout::WebSocketSend)),
-                    socket.lock().await.send_json(&send_msg)
-                ).await
-            {
-                let _ = tx.send(json!("")).await;
-            } else {
-                let _ = tx.send(WebSocketError::MessageSendError.into()).await;
+            match tokio::time::timeout(
+                Duration::from_millis(config.get_timeout(Timeout::WebSocketReceive)),
+                response
+            ).await {
+                Ok(Ok(result)) => {
+                    if let Some(error) = result.get("error") {
+                        notify_session(session_channel, SessionEvent::Failed(session_request.key, error.clone()));
+                    } else if result.get("result") != session_request.result.as_ref() {
+                        let replayed = result.get("result").cloned().unwrap_or(Value::Null);
+                        session.add(session_request.key.clone(), session_request.request, Some(replayed.clone()));
+                        notify_session(session_channel, SessionEvent::Resolved(session_request.key, replayed));
+                    }
+                }
+                Ok(Err(_dropped)) => {
+                    notify_session(session_channel, SessionEvent::Failed(session_request.key, WebSocketError::NotConnected.into()));
+                    return;
+                }
+                Err(_elapsed) => {
+                    pending_requests.cancel(request_id);
+                    notify_session(session_channel, SessionEvent::Failed(session_request.key, WebSocketError::MessageReceiveError.into()));
+                }
             }
+
         } else {
-            let _ = tx.send(WebSocketError::ErrorSenderChannel.into()).await;
+            pending_requests.cancel(request_id);
+            notify_session(session_channel, SessionEvent::Failed(session_request.key, WebSocketError::MessageSendError.into()));
         }
     }
+}
 
-    println!("Closing Sender Task...");
+pub async fn spawn_session_replay_task(
+    writer: Sender<WriteRequest>,
+    pending_requests: Arc<PendingRequests>,
+    session: Arc<Session>,
+    state_channel: Option<Sender<ConnectionState>>,
+    session_channel: Option<Sender<SessionEvent>>,
+    config: Arc<ClientConfig>
+) {
+
+    println!("Replaying Session Requests...");
+
+    replay_session(&writer, &pending_requests, &session, &session_channel, &config).await;
+
+    let _ = writer.send(WriteRequest::Resume).await;
+
+    notify_state(&state_channel, ConnectionState::Reconnected);
 }
 
+#[allow(clippy::too_many_arguments)]
+async fn reconnect(
+    reader: &mut SocketReader,
+    writer: &Sender<WriteRequest>,
+    url: &str,
+    pending_requests: &Arc<PendingRequests>,
+    session: &Arc<Session>,
+    closing: &Arc<AtomicBool>,
+    state_channel: &Option<Sender<ConnectionState>>,
+    session_channel: &Option<Sender<SessionEvent>>,
+    config: &Arc<ClientConfig>
+) -> bool {
+
+    if writer.send(WriteRequest::Suspend).await.is_err() {
+        return false;
+    }
+
+    pending_requests.clear();
+
+    if closing.load(Ordering::SeqCst) {
+        return false;
+    }
+
+    notify_state(state_channel, ConnectionState::Disconnected);
+
+    let max_attempts = config.get_reconnect_max_attempts();
+
+    let max_backoff = config.get_timeout(Timeout::ReconnectMax);
+
+    let mut backoff = config.get_timeout(Timeout::ReconnectMin);
+
+    let mut attempt: u32 = 0;
+
+    loop {
+
+        if closing.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        if max_attempts > 0 && attempt >= max_attempts {
+            println!("Giving up reconnecting to {} after {} attempts", url, attempt);
+            let _ = writer.send(WriteRequest::Abort).await;
+            pending_requests.clear();
+            notify_state(state_channel, ConnectionState::ReconnectFailed(attempt));
+            return false;
+        }
+
+        attempt += 1;
+
+        println!("Reconnecting to {} (attempt {})", url, attempt);
+
+        notify_state(state_channel, ConnectionState::Reconnecting(attempt));
+
+        let connection = connect(url).await.map_err(|e| e.to_string());
+
+        match connection {
+            Ok((new_reader, new_writer)) => {
+                if writer.send(WriteRequest::Replace(new_writer)).await.is_err() {
+                    return false;
+                }
+                *reader = new_reader;
+                break;
+            }
+            Err(e) => {
+                println!("Reconnect failed: {}", e);
+                tokio::time::sleep(Duration::from_millis(backoff)).await;
+                backoff = std::cmp::min(backoff.saturating_mul(2), max_backoff);
+            }
+        }
+    }
+
+    let writer = writer.clone();
+    let pending_requests = Arc::clone(pending_requests);
+    let session = Arc::clone(session);
+    let state_channel = state_channel.clone();
+    let session_channel = session_channel.clone();
+    let config = Arc::clone(config);
+
+    tokio::task::spawn(async move {
+        spawn_session_replay_task(writer, pending_requests, session, state_channel, session_channel, config).await;
+    });
+
+    return true;
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_receiver_task(
-    socket: Arc<Mutex<client::Online>>,
-    mut rx: Receiver<Value>,
+    mut reader: SocketReader,
+    writer: Sender<WriteRequest>,
     tx: Sender<Value>,
-    cb_channel: Option<Sender<(u64,Value)>>
+    cb_channel: Option<Sender<(u64,Value)>>,
+    pending_requests: Arc<PendingRequests>,
+    url: String,
+    session: Arc<Session>,
+    closing: Arc<AtomicBool>,
+    state_channel: Option<Sender<ConnectionState>>,
+    session_channel: Option<Sender<SessionEvent>>,
+    shutdown: Arc<Notify>,
+    config: Arc<ClientConfig>
 ) {
-    
+
+    let control_writer = writer.clone();
+
+    let mut send_control_frame = move |frame: Frame<'_>| {
+        let control_writer = control_writer.clone();
+        let frame = Frame::new(frame.fin, frame.opcode, None, Payload::Owned(frame.payload.to_vec()));
+        async move {
+            return control_writer.send(WriteRequest::Frame(frame)).await.map_err(|_e| WebSocketError::ErrorSenderChannel);
+        }
+    };
+
+    let (mut heartbeat, mut missed, mut heartbeat_lost) = start_heartbeat(&writer, &config);
+
     loop {
 
-        if let Ok(Some(result)) = tokio::time::timeout(
-            Duration::from_millis(CONFIG.get_timeout(Timeout::WebsocketClose)), 
-            rx.recv()
-        ).await {
-            let result_str: &str = &result.to_string();
-            match result_str.parse::<WebsocketCloseRequest>() {
-                Ok(_close) => {
-                    println!("WebSocket close has been requested.");
-                    break;
-                },
-                Err(_e) => {
+        if closing.load(Ordering::SeqCst) {
+            println!("WebSocket close has been requested.");
+            break;
+        }
 
-                }
+        let frame = tokio::select! {
+            frame = reader.read_frame(&mut send_control_frame) => Some(frame),
+            _ = heartbeat_lost.notified() => None,
+            _ = shutdown.notified() => {
+                println!("WebSocket close has been requested.");
+                break;
             }
-        }
+        };
 
-        let mut guard = socket.lock().await;
-
-        let message = if let Ok(result) =
-            tokio::time::timeout(
-                Duration::from_millis(CONFIG.get_timeout(Timeout::WebSocketReceive)),
-        guard.receive_frame()
-            ).await
-        {
-            match result {
-                Ok(message) => {
-                    message
-                },
-                Err(_e) => {
-                    drop(guard);
-                    break; // break the message loop then reconnect
-                 }
+        let message = match frame {
+            Some(Ok(message)) => message,
+            _ => {
+                heartbeat.abort();
+                if reconnect(&mut reader, &writer, &url, &pending_requests, &session, &closing, &state_channel, &session_channel, &config).await {
+                    (heartbeat, missed, heartbeat_lost) = start_heartbeat(&writer, &config);
+                    continue;
+                } else {
+                    break;
+                }
             }
-        } else {
-            drop(guard);
-            continue;
         };
 
+        missed.store(0, Ordering::SeqCst);
+
         match message.opcode {
             OpCode::Text => {
                 let msg_json = String::from_utf8_lossy(message.payload.as_ref()).to_string();
@@ -162,7 +460,7 @@ pub async fn spawn_receiver_task(
 
                 let subscription_response:Result<ChainResponse, serde_json::Error> = serde_json::from_str(&msg_json);
 
-                if let Ok(subs_res) = subscription_response.and_then(|res| if res.is_subcription_response() == true { Ok(res) } else { Err(serde_json::Error::custom("Not subscription response")) }) {
+                if let Ok(subs_res) = subscription_response.and_then(|res| if res.is_subcription_response() { Ok(res) } else { Err(serde_json::Error::custom("Not subscription response")) }) {
                     if let Some((callback_id, callback_param)) = subs_res.get_parsed_cb_response() {
                         if let Some(tx_cb) = &cb_channel {
                             let _ = tx_cb.send((callback_id, callback_param)).await;
@@ -173,21 +471,35 @@ pub async fn spawn_receiver_task(
                     } else {
                         println!("Error parsing callback id from subscriptions response.");
                     }
-                } else {
-                    if let Ok(message_json) = serde_json::from_str(&msg_json) {
-                        let _ = tx.send(message_json).await;
+                } else if let Ok(message_json) = serde_json::from_str::<Value>(&msg_json) {
+                    let request_id = message_json.get("id").and_then(|id| id.as_u64());
+                    if let Some(id) = request_id {
+                        if !pending_requests.resolve(id, message_json.clone()) {
+                            println!("No pending request for response id: {}", id);
+                            let _ = tx.try_send(message_json);
+                        }
                     } else {
-                        let _ = tx.send(WebSocketError::MessageReceiveError.into()).await;
+                        let _ = tx.try_send(message_json);
                     }
+                } else {
+                    let _ = tx.try_send(WebSocketError::MessageReceiveError.into());
                 }
 
             }
 
             OpCode::Close => {
                 println!("Websocket Close Requested");
-                break; // break the message loop then reconnect
+                heartbeat.abort();
+                if reconnect(&mut reader, &writer, &url, &pending_requests, &session, &closing, &state_channel, &session_channel, &config).await {
+                    (heartbeat, missed, heartbeat_lost) = start_heartbeat(&writer, &config);
+                    continue;
+                } else {
+                    break;
+                }
             }
 
+            OpCode::Ping | OpCode::Pong => {}
+
             _ => {
                 println!("Error Unexpected Error");
             }
@@ -195,5 +507,51 @@ pub async fn spawn_receiver_task(
 
     }
 
+    heartbeat.abort();
+
+    pending_requests.clear();
+
     println!("Closing Receiver Task...");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn callback_notices_run_in_order() {
+        let (tx_register, rx_register) = channel::<(u64, Box<SubscriptionCallback>)>(10);
+        let (_tx_unregister, rx_unregister) = channel::<u64>(10);
+        let (tx_call, rx_call) = channel::<(u64, Value)>(100);
+        let (tx_close, rx_close) = channel::<WebsocketCloseRequest>(10);
+        let (tx_seen, mut rx_seen) = unbounded_channel::<u64>();
+
+        tokio::task::spawn(spawn_callbacks_table(rx_register, rx_unregister, rx_call, rx_close, None));
+
+        let callback: Box<SubscriptionCallback> = Box::new(move |notice| {
+            let tx_seen = tx_seen.clone();
+            Box::pin(async move {
+                let index = notice.and_then(|notice| notice.as_u64()).unwrap_or(0);
+                tokio::time::sleep(Duration::from_millis(20 - index)).await;
+                let _ = tx_seen.send(index);
+                return Ok(());
+            })
+        });
+
+        tx_register.send((1, callback)).await.unwrap();
+
+        for index in 0..20 {
+            tx_call.send((1, json!(index))).await.unwrap();
+        }
+
+        let mut seen = Vec::new();
+        while seen.len() < 20 {
+            seen.push(rx_seen.recv().await.unwrap());
+        }
+
+        assert_eq!(seen, (0..20).collect::<Vec<u64>>());
+
+        let _ = tx_close.send(WebsocketCloseRequest::Close).await;
+    }
 }
\ No newline at end of file
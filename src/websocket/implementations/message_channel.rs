@@ -1,43 +1,29 @@
 use tokio::sync::mpsc::{Sender, Receiver, channel};
-use std::str::FromStr;
+use tokio::sync::oneshot;
+use fastwebsockets::Frame;
 use std::future::Future;
 use std::pin::Pin;
-use serde_json::{json, Value};
+use serde_json::Value;
 use std::time::Duration;
 use crate::websocket::errors::WebSocketError;
 use crate::websocket::interface::SubscriptionCallback;
+use crate::websocket::implementations::connection::SocketWriter;
 
 pub struct MessageChannel<T> {
-    sender: Option<Sender<T>>,
     receiver: Option<Receiver<T>>,
 }
 
 impl <T>MessageChannel<T> {
     pub fn new() -> Self {
         Self {
-            sender: None,
             receiver: None
         }
     }
 
-    pub fn create_channel(&mut self) -> (Sender<T>,Receiver<T>) {
-        let (sender, rx) = channel::<T>(100);
+    pub fn create_channel(&mut self) -> Sender<T> {
         let (tx, receiver) = channel::<T>(100);
-        self.sender = Some(sender);
         self.receiver = Some(receiver);
-        return (tx,rx);
-    }
-
-    pub async fn send(&self, msg: T) -> Result<(), WebSocketError> {
-        if let Some(sender) = &self.sender {
-            if let Ok(_res) = sender.send_timeout(msg, Duration::from_secs(1)).await {
-                return Ok(());
-            } else {
-                return Err(WebSocketError::ErrorSenderChannel);
-            }
-        } else {
-            return Err(WebSocketError::ErrorSenderChannel);
-        }
+        return tx;
     }
 
     pub async fn recv(&mut self) -> Result<Option<T>, WebSocketError> {
@@ -70,6 +56,7 @@ impl CallbackMessageChannel {
         }
     }
 
+    #[allow(clippy::type_complexity)]
     pub fn create_channel(&mut self) -> (
         Receiver<(u64, Box<SubscriptionCallback>)>,
         Receiver<u64>,
@@ -99,7 +86,7 @@ impl CallbackMessageChannel {
         );
     }
 
-    pub fn register_callback(&self, cb_id: u64, callback: Box<SubscriptionCallback>) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    pub fn register_callback(&self, cb_id: u64, callback: Box<SubscriptionCallback>) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
         
         let future = async move {
             if let Some(channel) = &self.cb_register {
@@ -116,7 +103,7 @@ impl CallbackMessageChannel {
         return Box::pin(future);   
     }
 
-    pub fn unregister_callback(&self, cb_id: u64) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    pub fn unregister_callback(&self, cb_id: u64) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
 
         let future = async move {
             if let Some(channel) = &self.cb_unregister {
@@ -133,7 +120,7 @@ impl CallbackMessageChannel {
         return Box::pin(future);   
     }
 
-    pub fn drop_callback_list(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    pub fn drop_callback_list(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
 
         let future = async move {
             if let Some(channel) = &self.ws_close {
@@ -152,26 +139,17 @@ impl CallbackMessageChannel {
 
 }
 
-pub enum WebsocketCloseRequest {
-    Close
-}
-
-impl FromStr for WebsocketCloseRequest {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s == "\"WebsocketCloseRequest::Close\"" {
-            return Ok(WebsocketCloseRequest::Close);
-        } else {
-            return Err(());
-        }
-    }
+pub enum WriteRequest {
+    Message(Value, oneshot::Sender<Result<(), WebSocketError>>),
+    Replay(Value, oneshot::Sender<Result<(), WebSocketError>>),
+    Frame(Frame<'static>),
+    Replace(SocketWriter),
+    Suspend,
+    Resume,
+    Abort,
+    Close(oneshot::Sender<Result<(), WebSocketError>>)
 }
 
-impl From<WebsocketCloseRequest> for Value {
-    fn from(request: WebsocketCloseRequest) -> Value {
-        match request {
-            WebsocketCloseRequest::Close => json!("WebsocketCloseRequest::Close"),
-        }
-    }
+pub enum WebsocketCloseRequest {
+    Close
 }
\ No newline at end of file
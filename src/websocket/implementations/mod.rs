@@ -1,4 +1,8 @@
 pub mod fast_websocket;
-pub mod message_channel;
-pub mod listeners;
-pub mod timeouts;
\ No newline at end of file
+pub(crate) mod connection;
+pub(crate) mod message_channel;
+pub(crate) mod listeners;
+pub mod timeouts;
+pub(crate) mod pending_requests;
+pub(crate) mod session;
+pub mod node_pool;
\ No newline at end of file
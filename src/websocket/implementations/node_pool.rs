@@ -0,0 +1,633 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::{channel, Sender, Receiver};
+use serde_json::{json, Value};
+use chrono::{NaiveDateTime, Utc};
+use futures::future::join_all;
+use crate::config::config::ClientConfig;
+use crate::websocket::implementations::timeouts::Timeout;
+
+use crate::websocket::{
+    interface::{IWebSocket, ConnectionState, SessionEvent},
+    errors::WebSocketError,
+    implementations::{
+        fast_websocket::FastWebsocketClient,
+        session::Session
+    }
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeHealth {
+    pub connected: bool,
+    pub healthy: bool,
+    pub latency: Option<Duration>,
+    pub head_block_age: Option<Duration>,
+    pub chain_id: Option<String>
+}
+
+struct PoolNode {
+    url: String,
+    socket: FastWebsocketClient
+}
+
+struct PoolState {
+    health: Vec<NodeHealth>,
+    state_receivers: Vec<Receiver<ConnectionState>>,
+    chain_id: Option<String>,
+    last_health_check: Option<Instant>
+}
+
+pub struct NodePoolClient {
+    nodes: Vec<PoolNode>,
+    state: Mutex<PoolState>,
+    checking: Mutex<()>,
+    active: std::sync::Mutex<Option<usize>>,
+    session: Session,
+    state_channel: Option<Sender<ConnectionState>>,
+    session_channel: Option<Sender<SessionEvent>>,
+    config: Arc<ClientConfig>
+}
+
+impl NodePoolClient {
+    pub fn new(urls: Vec<String>) -> Self {
+        return NodePoolClient::with_config(urls, Arc::new(ClientConfig::default()));
+    }
+
+    pub fn with_config(urls: Vec<String>, config: Arc<ClientConfig>) -> Self {
+        let mut nodes: Vec<PoolNode> = Vec::new();
+        let mut state_receivers: Vec<Receiver<ConnectionState>> = Vec::new();
+
+        for url in urls {
+            let mut socket = FastWebsocketClient::with_config(url.clone(), Arc::clone(&config));
+            let (tx_state, rx_state) = channel::<ConnectionState>(100);
+            socket.set_connection_state_channel(tx_state);
+            nodes.push(PoolNode { url, socket });
+            state_receivers.push(rx_state);
+        }
+
+        let health = vec![NodeHealth::default(); nodes.len()];
+
+        Self {
+            nodes,
+            state: Mutex::new(PoolState {
+                health,
+                state_receivers,
+                chain_id: None,
+                last_health_check: None
+            }),
+            checking: Mutex::new(()),
+            active: std::sync::Mutex::new(None),
+            session: Session::new(),
+            state_channel: None,
+            session_channel: None,
+            config
+        }
+    }
+
+    pub fn set_chain_id(&mut self, chain_id: String) {
+        self.state.get_mut().chain_id = Some(chain_id);
+    }
+
+    pub fn set_callback_channel(&mut self, channel: Sender<(u64, Value)>) {
+        for node in self.nodes.iter_mut() {
+            node.socket.set_callback_channel(channel.clone());
+        }
+    }
+
+    pub fn set_connection_state_channel(&mut self, channel: Sender<ConnectionState>) {
+        self.state_channel = Some(channel);
+    }
+
+    pub fn set_session_channel(&mut self, channel: Sender<SessionEvent>) {
+        for node in self.nodes.iter_mut() {
+            node.socket.set_session_channel(channel.clone());
+        }
+        self.session_channel = Some(channel);
+    }
+
+    pub fn get_active_url(&self) -> Option<String> {
+        return self.get_active().map(|index| self.nodes[index].url.clone());
+    }
+
+    pub async fn get_nodes_health(&self) -> Vec<(String, NodeHealth)> {
+        let state = self.state.lock().await;
+        return self.nodes.iter()
+            .zip(state.health.iter())
+            .map(|(node, health)| (node.url.clone(), health.clone()))
+            .collect();
+    }
+
+    fn get_active(&self) -> Option<usize> {
+        return *self.active.lock().unwrap();
+    }
+
+    fn set_active(&self, index: Option<usize>) {
+        *self.active.lock().unwrap() = index;
+    }
+
+    fn notify_state(&self, state: ConnectionState) {
+        if let Some(channel) = &self.state_channel {
+            let _ = channel.try_send(state);
+        }
+    }
+
+    fn notify_session(&self, event: SessionEvent) {
+        if let Some(channel) = &self.session_channel {
+            let _ = channel.try_send(event);
+        }
+    }
+
+    fn drain_node_states(state: &mut PoolState) {
+        for (index, receiver) in state.state_receivers.iter_mut().enumerate() {
+            while let Ok(node_state) = receiver.try_recv() {
+                match node_state {
+                    ConnectionState::Disconnected | ConnectionState::Reconnecting(_) | ConnectionState::ReconnectFailed(_) | ConnectionState::Closed => {
+                        state.health[index].healthy = false;
+                    }
+                    ConnectionState::Connected | ConnectionState::Reconnected => {}
+                }
+            }
+        }
+    }
+
+    async fn check_node(node: &PoolNode, connected: bool, chain_id: &Option<String>, config: &ClientConfig) -> NodeHealth {
+
+        if !connected && node.socket.connect().await.is_err() {
+            return NodeHealth::default();
+        }
+
+        let started = Instant::now();
+
+        let chain_id_response = node.socket.call(json!({
+            "method": "call",
+            "params": [0, "get_chain_id", []]
+        })).await;
+
+        let latency = started.elapsed();
+
+        let properties_response = node.socket.call(json!({
+            "method": "call",
+            "params": [0, "get_dynamic_global_properties", []]
+        })).await;
+
+        let node_chain_id = chain_id_response.ok()
+            .and_then(|response| response.get("result").and_then(|id| id.as_str()).map(String::from));
+
+        let head_block_age = properties_response.ok()
+            .and_then(|response| response.pointer("/result/time").and_then(|time| time.as_str()).map(String::from))
+            .and_then(|time| NaiveDateTime::parse_from_str(&time, "%Y-%m-%dT%H:%M:%S").ok())
+            .map(|time| (Utc::now().naive_utc() - time).to_std().unwrap_or(Duration::ZERO));
+
+        let max_head_age = Duration::from_millis(config.get_timeout(Timeout::PoolMaxHeadAge));
+
+        let chain_matches = match (chain_id, &node_chain_id) {
+            (Some(expected), Some(actual)) => expected == actual,
+            (None, Some(_actual)) => true,
+            (_, None) => false
+        };
+
+        let head_is_fresh = head_block_age.map(|age| age <= max_head_age).unwrap_or(false);
+
+        return NodeHealth {
+            connected: true,
+            healthy: chain_matches && head_is_fresh,
+            latency: Some(latency),
+            head_block_age,
+            chain_id: node_chain_id
+        };
+    }
+
+    async fn check_nodes(&self) {
+
+        let (connected, chain_id) = {
+            let mut state = self.state.lock().await;
+            NodePoolClient::drain_node_states(&mut state);
+            (state.health.iter().map(|health| health.connected).collect::<Vec<bool>>(), state.chain_id.clone())
+        };
+
+        let checks = self.nodes.iter()
+            .zip(connected)
+            .map(|(node, connected)| NodePoolClient::check_node(node, connected, &chain_id, &self.config));
+
+        let health = join_all(checks).await;
+
+        let mut guard = self.state.lock().await;
+
+        let state = &mut *guard;
+
+        state.health = health;
+
+        if state.chain_id.is_none() {
+            let mut chain_ids: HashMap<String, usize> = HashMap::new();
+
+            for health in state.health.iter().filter(|health| health.healthy) {
+                if let Some(chain_id) = &health.chain_id {
+                    *chain_ids.entry(chain_id.clone()).or_insert(0) += 1;
+                }
+            }
+
+            state.chain_id = chain_ids.into_iter()
+                .max_by_key(|(_chain_id, count)| *count)
+                .map(|(chain_id, _count)| chain_id);
+
+            for health in state.health.iter_mut() {
+                if health.chain_id != state.chain_id {
+                    health.healthy = false;
+                }
+            }
+        }
+
+        state.last_health_check = Some(Instant::now());
+    }
+
+    pub async fn health_check(&self) {
+        let _checking = self.checking.lock().await;
+        self.check_nodes().await;
+    }
+
+    fn best_node(state: &PoolState) -> Option<usize> {
+        return state.health.iter()
+            .enumerate()
+            .filter(|(_index, health)| health.healthy)
+            .min_by_key(|(_index, health)| (health.latency, health.head_block_age))
+            .map(|(index, _health)| index);
+    }
+
+    async fn switch_to(&self, index: usize) -> Result<(), WebSocketError> {
+
+        let session_requests = self.session.requests();
+
+        let previous_active = self.get_active();
+
+        if let Some(previous) = previous_active {
+            for session_request in session_requests.iter() {
+                self.nodes[previous].socket.remove_session_request(&session_request.key);
+            }
+            let previous_healthy = self.state.lock().await.health[previous].healthy;
+            if previous != index && previous_healthy {
+                let _ = self.nodes[previous].socket.call(json!({
+                    "method": "call",
+                    "params": [0, "cancel_all_subscriptions", []]
+                })).await;
+            }
+        }
+
+        println!("Switching active node to {}", self.nodes[index].url);
+
+        let node = &self.nodes[index];
+
+        for session_request in session_requests {
+            let response = node.socket.call(session_request.request.clone()).await?;
+
+            if let Some(error) = response.get("error") {
+                self.notify_session(SessionEvent::Failed(session_request.key, error.clone()));
+                continue;
+            }
+
+            let result = response.get("result").cloned();
+
+            if result != session_request.result {
+                self.session.add(session_request.key.clone(), session_request.request.clone(), result.clone());
+                self.notify_session(SessionEvent::Resolved(session_request.key.clone(), result.clone().unwrap_or(Value::Null)));
+            }
+
+            node.socket.add_session_request(session_request.key, session_request.request, result);
+        }
+
+        self.set_active(Some(index));
+
+        if previous_active.is_some() {
+            self.notify_state(ConnectionState::Reconnected);
+        } else {
+            self.notify_state(ConnectionState::Connected);
+        }
+
+        return Ok(());
+    }
+
+    async fn healthy_active(&self) -> Option<usize> {
+        let mut state = self.state.lock().await;
+
+        NodePoolClient::drain_node_states(&mut state);
+
+        let health_check_due = state.last_health_check
+            .map(|checked| checked.elapsed() >= Duration::from_millis(self.config.get_timeout(Timeout::PoolHealthCheck)))
+            .unwrap_or(true);
+
+        return self.get_active().filter(|index| !health_check_due && state.health[*index].healthy);
+    }
+
+    async fn switch_to_best(&self) -> Result<usize, WebSocketError> {
+        loop {
+            let best = NodePoolClient::best_node(&*self.state.lock().await);
+
+            if let Some(index) = best {
+                if self.switch_to(index).await.is_ok() {
+                    return Ok(index);
+                }
+                self.mark_unhealthy(index).await;
+            } else {
+                self.set_active(None);
+                self.notify_state(ConnectionState::Disconnected);
+                return Err(WebSocketError::NotConnected);
+            }
+        }
+    }
+
+    async fn ensure_active(&self) -> Result<usize, WebSocketError> {
+
+        if let Some(index) = self.healthy_active().await {
+            return Ok(index);
+        }
+
+        let _checking = self.checking.lock().await;
+
+        if let Some(index) = self.healthy_active().await {
+            return Ok(index);
+        }
+
+        self.check_nodes().await;
+
+        if let Some(index) = self.get_active() {
+            if self.state.lock().await.health[index].healthy {
+                return Ok(index);
+            }
+        }
+
+        return self.switch_to_best().await;
+    }
+
+    async fn mark_unhealthy(&self, index: usize) {
+        self.state.lock().await.health[index].healthy = false;
+    }
+}
+
+fn is_retryable(msg: &Value) -> bool {
+    return msg.pointer("/params/1")
+        .and_then(|method| method.as_str())
+        .map(|method| !method.starts_with("broadcast_"))
+        .unwrap_or(false);
+}
+
+impl IWebSocket for NodePoolClient {
+
+    fn connect(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+        let future = async {
+            let _checking = self.checking.lock().await;
+
+            self.check_nodes().await;
+
+            let best = NodePoolClient::best_node(&*self.state.lock().await);
+
+            if let Some(index) = best {
+                return self.switch_to(index).await;
+            } else {
+                return Err(WebSocketError::ConnectionError);
+            }
+        };
+        return Box::pin(future);
+    }
+
+    fn send(&self, msg: Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+        let future = async move {
+            let index = self.ensure_active().await?;
+            return self.nodes[index].socket.send(msg).await;
+        };
+        return Box::pin(future);
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+        let future = async {
+            if let Some(index) = self.get_active() {
+                return self.nodes[index].socket.receive().await;
+            } else {
+                return Err(WebSocketError::NotConnected);
+            }
+        };
+        return Box::pin(future);
+    }
+
+    fn call(&self, msg: Value) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+        let future = async move {
+            let index = self.ensure_active().await?;
+
+            match self.nodes[index].socket.call(msg.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    println!("Call failed on {}: {}", self.nodes[index].url, error);
+                    self.mark_unhealthy(index).await;
+                    if !is_retryable(&msg) {
+                        return Err(error);
+                    }
+                }
+            }
+
+            let index = self.ensure_active().await?;
+            return self.nodes[index].socket.call(msg).await;
+        };
+        return Box::pin(future);
+    }
+
+    fn add_session_request(&self, key: String, request: Value, result: Option<Value>) {
+        if let Some(index) = self.get_active() {
+            self.nodes[index].socket.add_session_request(key.clone(), request.clone(), result.clone());
+        }
+        self.session.add(key, request, result);
+    }
+
+    fn remove_session_request(&self, key: &str) {
+        if let Some(index) = self.get_active() {
+            self.nodes[index].socket.remove_session_request(key);
+        }
+        self.session.remove(key);
+    }
+
+    fn close(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
+        let future = async {
+            let _checking = self.checking.lock().await;
+
+            let connected: Vec<bool> = self.state.lock().await.health.iter().map(|health| health.connected).collect();
+
+            let mut result = Ok(());
+
+            for (node, connected) in self.nodes.iter().zip(connected) {
+                if connected {
+                    if let Err(error) = node.socket.close().await {
+                        result = Err(error);
+                    }
+                }
+            }
+
+            for health in self.state.lock().await.health.iter_mut() {
+                *health = NodeHealth::default();
+            }
+
+            self.set_active(None);
+            self.notify_state(ConnectionState::Closed);
+
+            return result;
+        };
+        return Box::pin(future);
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tungstenite::{accept, Message};
+
+    const CHAIN_A: &str = "4018d7844c78f6a6c41c6a552b898022310fc5dec06da467ee7905a8dad512c8";
+    const CHAIN_B: &str = "39f5e2ede1f8bc1a3a54a7914414e3779e33193f1f5693510e73cb7a87617447";
+
+    struct MockNode {
+        url: String,
+        alive: Arc<AtomicBool>
+    }
+
+    fn respond(name: &str, chain_id: &str, head_age: i64, request: &Value) -> Value {
+        let method = request.pointer("/params/1").and_then(|method| method.as_str()).unwrap_or("");
+
+        let result = match method {
+            "get_chain_id" => json!(chain_id),
+            "get_dynamic_global_properties" => {
+                let time = Utc::now().naive_utc() - chrono::Duration::seconds(head_age);
+                json!({"time": time.format("%Y-%m-%dT%H:%M:%S").to_string()})
+            }
+            _ => json!(name)
+        };
+
+        return json!({"id": request["id"], "jsonrpc": "2.0", "result": result});
+    }
+
+    fn spawn_mock_node(name: &'static str, chain_id: &'static str, head_age: i64) -> MockNode {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let alive = Arc::new(AtomicBool::new(true));
+        let node_alive = Arc::clone(&alive);
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if !node_alive.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                let alive = Arc::clone(&node_alive);
+
+                std::thread::spawn(move || {
+                    let mut ws = if let Ok(ws) = accept(stream) { ws } else { return; };
+
+                    while let Ok(message) = ws.read_message() {
+                        if !alive.load(Ordering::SeqCst) {
+                            return;
+                        }
+
+                        if let Message::Text(text) = message {
+                            let request: Value = serde_json::from_str(&text).unwrap();
+                            let response = respond(name, chain_id, head_age, &request);
+                            if ws.write_message(Message::Text(response.to_string())).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        return MockNode { url, alive };
+    }
+
+    fn pool(nodes: &[&MockNode]) -> NodePoolClient {
+        let config = ClientConfig::builder()
+            .timeout(Timeout::WebSocketReceive, 500)
+            .timeout(Timeout::ReconnectMin, 100)
+            .build()
+            .unwrap();
+
+        return NodePoolClient::with_config(nodes.iter().map(|node| node.url.clone()).collect(), Arc::new(config));
+    }
+
+    fn node_call(method: &str) -> Value {
+        return json!({"method": "call", "params": [0, method, []]});
+    }
+
+    #[tokio::test]
+    async fn call_fails_over_to_healthy_node() {
+        let first = spawn_mock_node("first", CHAIN_A, 0);
+        let second = spawn_mock_node("second", CHAIN_A, 0);
+        let pool = pool(&[&first, &second]);
+
+        pool.connect().await.unwrap();
+
+        let active = pool.get_active_url().unwrap();
+        let (dead, survivor) = if active == first.url { (&first, "second") } else { (&second, "first") };
+
+        dead.alive.store(false, Ordering::SeqCst);
+
+        let response = pool.call(node_call("get_node_name")).await.unwrap();
+
+        assert_eq!(response["result"], json!(survivor));
+        assert_ne!(pool.get_active_url().unwrap(), dead.url);
+    }
+
+    #[tokio::test]
+    async fn broadcast_is_not_retried_on_another_node() {
+        let first = spawn_mock_node("first", CHAIN_A, 0);
+        let second = spawn_mock_node("second", CHAIN_A, 0);
+        let pool = pool(&[&first, &second]);
+
+        pool.connect().await.unwrap();
+
+        let active = pool.get_active_url().unwrap();
+        let dead = if active == first.url { &first } else { &second };
+
+        dead.alive.store(false, Ordering::SeqCst);
+
+        assert!(pool.call(node_call("broadcast_transaction")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn stale_head_block_node_is_not_selected() {
+        let stale = spawn_mock_node("stale", CHAIN_A, 600);
+        let fresh = spawn_mock_node("fresh", CHAIN_A, 0);
+        let pool = pool(&[&stale, &fresh]);
+
+        pool.connect().await.unwrap();
+
+        assert_eq!(pool.get_active_url(), Some(fresh.url.clone()));
+
+        let health = pool.get_nodes_health().await;
+        let (_url, stale_health) = health.iter().find(|(url, _health)| *url == stale.url).unwrap();
+
+        assert!(stale_health.connected);
+        assert!(!stale_health.healthy);
+        assert!(stale_health.head_block_age.unwrap() >= Duration::from_secs(600));
+    }
+
+    #[tokio::test]
+    async fn chain_id_follows_node_majority() {
+        let first = spawn_mock_node("first", CHAIN_A, 0);
+        let fork = spawn_mock_node("fork", CHAIN_B, 0);
+        let second = spawn_mock_node("second", CHAIN_A, 0);
+        let pool = pool(&[&first, &fork, &second]);
+
+        pool.connect().await.unwrap();
+
+        assert_ne!(pool.get_active_url(), Some(fork.url.clone()));
+
+        for (url, health) in pool.get_nodes_health().await {
+            if url == fork.url {
+                assert!(!health.healthy);
+            } else {
+                assert!(health.healthy);
+                assert_eq!(health.chain_id.as_deref(), Some(CHAIN_A));
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::oneshot;
+use serde_json::Value;
+
+pub struct PendingRequests {
+    next_id: AtomicU64,
+    requests: Mutex<HashMap<u64, oneshot::Sender<Value>>>
+}
+
+impl PendingRequests {
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            requests: Mutex::new(HashMap::new())
+        }
+    }
+
+    pub fn register(&self) -> (u64, oneshot::Receiver<Value>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel::<Value>();
+        self.requests.lock().unwrap().insert(id, tx);
+        return (id, rx);
+    }
+
+    pub fn resolve(&self, id: u64, response: Value) -> bool {
+        if let Some(tx) = self.requests.lock().unwrap().remove(&id) {
+            return tx.send(response).is_ok();
+        } else {
+            return false;
+        }
+    }
+
+    pub fn cancel(&self, id: u64) {
+        self.requests.lock().unwrap().remove(&id);
+    }
+
+    pub fn clear(&self) {
+        self.requests.lock().unwrap().clear();
+    }
+}
\ No newline at end of file
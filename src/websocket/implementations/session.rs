@@ -0,0 +1,41 @@
+use std::sync::Mutex;
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
+pub struct SessionRequest {
+    pub key: String,
+    pub request: Value,
+    pub result: Option<Value>
+}
+
+pub struct Session {
+    requests: Mutex<Vec<SessionRequest>>
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self {
+            requests: Mutex::new(Vec::new())
+        }
+    }
+
+    pub fn add(&self, key: String, request: Value, result: Option<Value>) {
+        let mut requests = self.requests.lock().unwrap();
+
+        let session_request = SessionRequest { key, request, result };
+
+        if let Some(existing) = requests.iter_mut().find(|req| req.key == session_request.key) {
+            *existing = session_request;
+        } else {
+            requests.push(session_request);
+        }
+    }
+
+    pub fn remove(&self, key: &str) {
+        self.requests.lock().unwrap().retain(|req| req.key != key);
+    }
+
+    pub fn requests(&self) -> Vec<SessionRequest> {
+        return self.requests.lock().unwrap().clone();
+    }
+}
@@ -1,5 +1,45 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Timeout {
     WebSocketSend,
     WebSocketReceive,
-    WebsocketClose
+    WebsocketClose,
+    ReconnectMin,
+    ReconnectMax,
+    PoolHealthCheck,
+    PoolMaxHeadAge,
+    Heartbeat,
+    BlockFetchMin,
+    BlockFetchMax
+}
+
+impl Timeout {
+    pub fn all() -> [Timeout; 10] {
+        return [
+            Timeout::WebSocketSend,
+            Timeout::WebSocketReceive,
+            Timeout::WebsocketClose,
+            Timeout::ReconnectMin,
+            Timeout::ReconnectMax,
+            Timeout::PoolHealthCheck,
+            Timeout::PoolMaxHeadAge,
+            Timeout::Heartbeat,
+            Timeout::BlockFetchMin,
+            Timeout::BlockFetchMax
+        ];
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Timeout::WebSocketSend => "ws_send",
+            Timeout::WebSocketReceive => "ws_receive",
+            Timeout::WebsocketClose => "ws_close",
+            Timeout::ReconnectMin => "ws_reconnect_min",
+            Timeout::ReconnectMax => "ws_reconnect_max",
+            Timeout::PoolHealthCheck => "pool_health_check",
+            Timeout::PoolMaxHeadAge => "pool_max_head_age",
+            Timeout::Heartbeat => "ws_heartbeat",
+            Timeout::BlockFetchMin => "block_fetch_min",
+            Timeout::BlockFetchMax => "block_fetch_max"
+        }
+    }
 }
\ No newline at end of file
@@ -5,9 +5,28 @@ use std::pin::Pin;
 
 pub type SubscriptionCallback = dyn Fn(Option<Value>) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + Sync >> + Send + Sync;
 
-pub trait IWebSocket {
-    fn connect(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>>;
-    fn send(&mut self, msg: Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>>;
-    fn receive(&mut self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + '_>>;
-    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>>;
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Disconnected,
+    Reconnecting(u32),
+    Reconnected,
+    ReconnectFailed(u32),
+    Closed
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionEvent {
+    Resolved(String, Value),
+    Failed(String, Value)
+}
+
+pub trait IWebSocket: Send + Sync {
+    fn connect(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>>;
+    fn send(&self, msg: Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>>;
+    fn receive(&self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>>;
+    fn call(&self, msg: Value) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>>;
+    fn add_session_request(&self, key: String, request: Value, result: Option<Value>);
+    fn remove_session_request(&self, key: &str);
+    fn close(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>>;
 }
\ No newline at end of file
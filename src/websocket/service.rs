@@ -2,30 +2,44 @@ use crate::websocket::{interface::IWebSocket, errors::WebSocketError};
 use serde_json::Value;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
-pub struct WebSocket<'a> {
-    socket: &'a mut dyn IWebSocket
+#[derive(Clone)]
+pub struct WebSocket {
+    socket: Arc<dyn IWebSocket>
 }
 
-impl <'a>WebSocket<'a> {
+impl WebSocket {
 
-    pub fn new(socket: &'a mut dyn IWebSocket) -> Self {
-        Self { socket }
+    pub fn new<S: IWebSocket + 'static>(socket: S) -> Self {
+        Self { socket: Arc::new(socket) }
     }
 
-    pub fn connect(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    pub fn connect(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
         return self.socket.connect();
     }
 
-    pub fn send(&mut self, msg:Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    pub fn send(&self, msg:Value) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
         return self.socket.send(msg);
     }
 
-    pub fn receive(&mut self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + '_>> {
+    pub fn receive(&self) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
         return self.socket.receive();
     }
 
-    pub fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + '_>> {
+    pub fn call(&self, msg:Value) -> Pin<Box<dyn Future<Output = Result<Value, WebSocketError>> + Send + '_>> {
+        return self.socket.call(msg);
+    }
+
+    pub fn add_session_request(&self, key: String, request: Value, result: Option<Value>) {
+        self.socket.add_session_request(key, request, result);
+    }
+
+    pub fn remove_session_request(&self, key: &str) {
+        self.socket.remove_session_request(key);
+    }
+
+    pub fn close(&self) -> Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + '_>> {
         return self.socket.close();
     }
 